@@ -1,12 +1,14 @@
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GrandpaConfig, RuntimeGenesisConfig, Signature,
-	SudoConfig, SystemConfig, WASM_BINARY,
+	AccountId, AssetsConfig, AuraConfig, BalancesConfig, BudgetAssetId, ElectoralCommissionConfig,
+	GrandpaConfig, RuntimeGenesisConfig, Signature, SudoConfig, SystemConfig, Voting, VotingConfig,
+	WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
-use sp_core::{sr25519, Pair, Public};
+use sp_core::{crypto::Ss58Codec, sr25519, Pair, Public};
 use sp_runtime::traits::{IdentifyAccount, Verify};
+use std::{collections::BTreeSet, path::Path};
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
@@ -59,6 +61,13 @@ pub fn development_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 				],
+				// Electoral commissioners
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+				],
+				// Voter roll
+				vec![],
 				true,
 			)
 		},
@@ -107,6 +116,64 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
+				// Electoral commissioners
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+					get_account_id_from_seed::<sr25519::Public>("Charlie"),
+				],
+				// Voter roll
+				vec![],
+				true,
+			)
+		},
+		// Bootnodes
+		vec![],
+		// Telemetry
+		None,
+		// Protocol ID
+		None,
+		// Properties
+		None,
+		None,
+		// Extensions
+		None,
+	))
+}
+
+/// A local testnet whose citizens include the voter roll at `path`. The roll is either a CSV file
+/// with an SS58 address in the first column of each line, after an optional header, or a JSON
+/// array of SS58 addresses.
+pub fn voter_roll_config(path: &Path) -> Result<ChainSpec, String> {
+	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+	let voter_roll = read_voter_roll(path)?;
+
+	Ok(ChainSpec::from_genesis(
+		// Name
+		"Voter Roll Testnet",
+		// ID
+		"voter_roll_testnet",
+		ChainType::Local,
+		move || {
+			testnet_genesis(
+				wasm_binary,
+				// Initial PoA authorities
+				vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")],
+				// Sudo account
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				// Pre-funded accounts
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+					get_account_id_from_seed::<sr25519::Public>("Charlie"),
+				],
+				// Electoral commissioners
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+					get_account_id_from_seed::<sr25519::Public>("Charlie"),
+				],
+				voter_roll.clone(),
 				true,
 			)
 		},
@@ -124,12 +191,44 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 	))
 }
 
+/// Reads the citizens of a CSV or JSON voter roll, rejecting invalid or repeated addresses.
+fn read_voter_roll(path: &Path) -> Result<Vec<AccountId>, String> {
+	let contents = std::fs::read_to_string(path)
+		.map_err(|e| format!("Cannot read voter roll {}: {}", path.display(), e))?;
+
+	let addresses: Vec<String> = match path.extension().and_then(|extension| extension.to_str()) {
+		Some("json") => serde_json::from_str(&contents)
+			.map_err(|e| format!("Invalid JSON voter roll {}: {}", path.display(), e))?,
+		_ => contents
+			.lines()
+			.filter_map(|line| line.split(',').next())
+			.map(|address| address.trim().to_string())
+			.filter(|address| !address.is_empty() && address != "address")
+			.collect(),
+	};
+
+	let mut seen = BTreeSet::new();
+	addresses
+		.iter()
+		.map(|address| {
+			let citizen = AccountId::from_ss58check(address)
+				.map_err(|e| format!("Invalid address {} in voter roll: {:?}", address, e))?;
+			match seen.insert(citizen.clone()) {
+				true => Ok(citizen),
+				false => Err(format!("Address {} appears twice in voter roll", address)),
+			}
+		})
+		.collect()
+}
+
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
 	wasm_binary: &[u8],
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	commissioners: Vec<AccountId>,
+	voter_roll: Vec<AccountId>,
 	_enable_println: bool,
 ) -> RuntimeGenesisConfig {
 	RuntimeGenesisConfig {
@@ -154,5 +253,24 @@ fn testnet_genesis(
 			key: Some(root_key),
 		},
 		transaction_payment: Default::default(),
+		assets: AssetsConfig {
+			// The asset budgets are issued in, administered by the voting pallet.
+			assets: vec![(BudgetAssetId::get(), Voting::account_id(), true, 1)],
+			..Default::default()
+		},
+		electoral_commission: ElectoralCommissionConfig {
+			// Two thirds of the commission are needed to run budget elections.
+			members: commissioners,
+			..Default::default()
+		},
+		voting: VotingConfig {
+			// Register the endowed accounts as citizens, along with the voter roll.
+			citizens: voter_roll
+				.into_iter()
+				.filter(|citizen| !endowed_accounts.contains(citizen))
+				.chain(endowed_accounts.iter().cloned())
+				.collect(),
+			..Default::default()
+		},
 	}
 }
@@ -42,8 +42,12 @@ impl SubstrateCli for Cli {
 		Ok(match id {
 			"dev" => Box::new(chain_spec::development_config()?),
 			"" | "local" => Box::new(chain_spec::local_testnet_config()?),
-			path =>
-				Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(path))?),
+			// `voter-roll:<path>` registers the citizens of a CSV or JSON voter roll
+			id => match id.strip_prefix("voter-roll:") {
+				Some(path) => Box::new(chain_spec::voter_roll_config(std::path::Path::new(path))?),
+				None =>
+					Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(id))?),
+			},
 		})
 	}
 }
@@ -0,0 +1,259 @@
+//! Rules turning the citizens' ballots into the relative funding of each department.
+use frame_support::{
+	pallet_prelude::*,
+	sp_runtime::{helpers_128bit::multiply_by_rational_with_rounding, Rounding},
+};
+use scale_info::prelude::vec::Vec;
+
+/// A citizen's votes, one entry per department, with the precision of `FixedU128`. Votes against
+/// a department are negative.
+pub type Ballot = Vec<i128>;
+
+/// The precision shares of the budget are computed with, i.e. what a whole budget amounts to.
+const WHOLE: u128 = 1_000_000_000;
+
+/// How finely shares are told apart by rules taking their median: to a thousandth of the budget.
+const SHARE_STEPS: u32 = 1_000;
+
+/// The share of the budget each step of a share histogram amounts to.
+const SHARE_STEP: u128 = WHOLE / SHARE_STEPS as u128;
+
+/// The most departments ballots can be counted for.
+pub type MaxDepartments = ConstU32<16>;
+
+/// How many steps share histograms have, from no share to the whole budget.
+pub type HistogramSteps = ConstU32<{ SHARE_STEPS + 1 }>;
+
+/// How the ballots of a budget election are aggregated into the relative funding of each
+/// department. All rules can be run on the same ballots, so they can be compared.
+#[derive(
+	Clone,
+	Copy,
+	Default,
+	PartialEq,
+	Eq,
+	RuntimeDebug,
+	Encode,
+	Decode,
+	TypeInfo,
+	MaxEncodedLen,
+	frame_support::Serialize,
+	frame_support::Deserialize,
+)]
+#[serde(crate = "frame_support::serde")]
+pub enum Aggregator {
+	/// Departments are funded with the sum of the votes they received, once votes against them
+	/// are subtracted.
+	#[default]
+	Sum,
+	/// Every ballot is normalised into shares of the budget before being summed, so all citizens
+	/// weigh the same however much credit they spent. Votes against departments only reduce the
+	/// outlay.
+	NormalisedShares,
+	/// The Independent Markets rule: a moving-phantom median over the normalised shares, which is
+	/// proportional and does not reward citizens for misreporting their preferences. Votes
+	/// against departments only reduce the outlay.
+	IndependentMarkets,
+}
+
+impl Aggregator {
+	/// The weight of each of the `departments`, relative to each other, according to `ballots`.
+	/// `None` if the votes add up to more than fits in the weights.
+	pub fn aggregate(&self, ballots: &[Ballot], departments: usize) -> Option<Vec<u128>> {
+		let mut tally = Tally::default();
+		for ballot in ballots {
+			tally.add(ballot)?;
+		}
+		Some(tally.weights(*self, departments))
+	}
+}
+
+/// The ballots of a budget election counted so far, summed up so that they take the same space
+/// however many citizens voted, and can be counted a batch of citizens at a time.
+#[derive(Clone, Default, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct Tally {
+	/// The sum of the votes of each department.
+	pub votes: BoundedVec<i128, MaxDepartments>,
+	/// The sum of the shares of the budget each department was given.
+	pub shares: BoundedVec<u128, MaxDepartments>,
+	/// How many ballots gave each department each step of the share histogram.
+	pub histograms: BoundedVec<BoundedVec<u32, HistogramSteps>, MaxDepartments>,
+	/// How many ballots funded at least one department.
+	pub funding_ballots: u32,
+}
+
+impl Tally {
+	/// Counts `ballot`. `None` if the votes add up to more than can be counted, or the ballot has
+	/// more than `MaxDepartments` departments.
+	pub fn add(&mut self, ballot: &Ballot) -> Option<()> {
+		for (department, votes) in ballot.iter().enumerate() {
+			let total = entry(&mut self.votes, department)?;
+			*total = total.checked_add(*votes)?;
+		}
+		if let Some(shares) = shares_of(ballot)? {
+			for (department, share) in shares.into_iter().enumerate() {
+				let total = entry(&mut self.shares, department)?;
+				*total = total.checked_add(share)?;
+				let ballots = entry(entry(&mut self.histograms, department)?, step_of(share))?;
+				*ballots = ballots.checked_add(1)?;
+			}
+			self.funding_ballots = self.funding_ballots.checked_add(1)?;
+		}
+		Some(())
+	}
+
+	/// The weight of each of the `departments`, relative to each other, according to
+	/// `aggregator`.
+	pub fn weights(&self, aggregator: Aggregator, departments: usize) -> Vec<u128> {
+		match aggregator {
+			Aggregator::Sum => (0..departments)
+				.map(|department| {
+					self.votes.get(department).map_or(0, |votes| (*votes).max(0) as u128)
+				})
+				.collect(),
+			Aggregator::NormalisedShares => (0..departments)
+				.map(|department| self.shares.get(department).copied().unwrap_or(0))
+				.collect(),
+			Aggregator::IndependentMarkets =>
+				independent_markets(&self.histograms, self.funding_ballots, departments),
+		}
+	}
+}
+
+/// The entry at `index` of `values`, which grows up to it if needed. `None` if it cannot.
+fn entry<V: Default, S: Get<u32>>(values: &mut BoundedVec<V, S>, index: usize) -> Option<&mut V> {
+	while values.len() <= index {
+		values.try_push(V::default()).ok()?;
+	}
+	values.get_mut(index)
+}
+
+/// The step of a share histogram `share` falls in.
+fn step_of(share: u128) -> usize {
+	(share / SHARE_STEP).min(SHARE_STEPS as u128) as usize
+}
+
+/// Shares `outlay` out in whole units proportionally to `weights`, by the largest remainder
+/// method: each weight receives the whole part of its quota, and the units left over go to the
+/// largest remainders, the first weights winning ties. `None` if the quotas do not fit in a
+/// `u128`.
+pub fn apportion(outlay: u128, weights: &[u128]) -> Option<Vec<u128>> {
+	let total = weights.iter().try_fold(0u128, |total, weight| total.checked_add(*weight))?;
+	if total == 0 {
+		return Some(weights.iter().map(|_| 0).collect())
+	}
+	// Quotas are worked out with the precision of shares, enough to rank their remainders
+	let quotas = weights
+		.iter()
+		.map(|weight| {
+			multiply_by_rational_with_rounding(
+				outlay.checked_mul(WHOLE)?,
+				*weight,
+				total,
+				Rounding::Down,
+			)
+		})
+		.collect::<Option<Vec<u128>>>()?;
+	let mut funds: Vec<u128> = quotas.iter().map(|quota| quota / WHOLE).collect();
+	let left = outlay.saturating_sub(funds.iter().sum());
+	let mut largest: Vec<usize> = (0..weights.len()).collect();
+	largest.sort_by_key(|index| core::cmp::Reverse(quotas[*index] % WHOLE));
+	for index in largest.into_iter().take(left as usize) {
+		funds[index] += 1;
+	}
+	Some(funds)
+}
+
+/// The share of the budget a citizen gives to each department, leaving out votes against
+/// departments. `Some(None)` if they did not fund any, and `None` if their ballot adds up to more
+/// than fits in a `u128`.
+fn shares_of(ballot: &Ballot) -> Option<Option<Vec<u128>>> {
+	let funds: Vec<u128> = ballot.iter().map(|votes| (*votes).max(0) as u128).collect();
+	let total = funds.iter().try_fold(0u128, |total, funds| total.checked_add(*funds))?;
+	if total == 0 {
+		return Some(None)
+	}
+	funds
+		.iter()
+		.map(|funds| multiply_by_rational_with_rounding(*funds, WHOLE, total, Rounding::Down))
+		.collect::<Option<Vec<u128>>>()
+		.map(Some)
+}
+
+/// Each department receives the median of the `n` citizens' shares and `n + 1` phantom shares,
+/// `min(1, t * (n - k))` for `k` in `0..=n`. `t` grows until the medians fill the budget. The
+/// citizens' shares are taken from `histograms`, so they are told apart to a step of the
+/// histogram.
+fn independent_markets(
+	histograms: &[BoundedVec<u32, HistogramSteps>],
+	n: u32,
+	departments: usize,
+) -> Vec<u128> {
+	let n = n as u128;
+	if n == 0 {
+		return (0..departments).map(|_| 0).collect()
+	}
+
+	// How many citizens gave each department each step of the histogram, or less
+	let cumulative: Vec<Vec<u128>> = (0..departments)
+		.map(|department| {
+			let histogram = histograms.get(department);
+			let mut total = 0;
+			(0..=SHARE_STEPS as usize)
+				.map(|step| {
+					total += histogram.and_then(|histogram| histogram.get(step)).map_or(0, |b| *b)
+						as u128;
+					total
+				})
+				.collect()
+		})
+		.collect();
+
+	let medians = |t: u128| -> Vec<u128> {
+		cumulative
+			.iter()
+			.map(|cumulative| {
+				// How many of the citizens' and phantom shares are at most `value`
+				let at_most = |value: u128| {
+					let shares = cumulative[step_of(value)];
+					let phantoms = match t {
+						0 => n + 1,
+						_ if value >= WHOLE => n + 1,
+						_ => (value / t).min(n) + 1,
+					};
+					shares + phantoms
+				};
+				// The median is the lowest of the 2n + 1 shares with n + 1 of them at most it
+				let share = (0..=SHARE_STEPS as usize)
+					.filter(|step| {
+						cumulative[*step] > step.checked_sub(1).map_or(0, |s| cumulative[s])
+					})
+					.map(|step| step as u128 * SHARE_STEP)
+					.find(|share| at_most(*share) > n);
+				let phantom = |j: u128| t.saturating_mul(j).min(WHOLE);
+				let (mut low, mut high) = (0, n);
+				while low < high {
+					let j = (low + high) / 2;
+					if at_most(phantom(j)) > n {
+						high = j;
+					} else {
+						low = j + 1;
+					}
+				}
+				share.map_or(phantom(low), |share| share.min(phantom(low)))
+			})
+			.collect()
+	};
+
+	// The medians only grow with `t`, so the smallest `t` filling the budget is searched for
+	let (mut low, mut high) = (0, WHOLE);
+	while low < high {
+		let t = (low + high) / 2;
+		if medians(t).iter().sum::<u128>() < WHOLE {
+			low = t + 1;
+		} else {
+			high = t;
+		}
+	}
+	medians(low)
+}
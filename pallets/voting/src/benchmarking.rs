@@ -5,30 +5,283 @@ use super::*;
 #[allow(unused)]
 use crate::Pallet as Voting;
 use frame_benchmarking::v2::*;
+use frame_support::{sp_runtime::Perbill, traits::EnsureOrigin, BoundedVec};
 use frame_system::RawOrigin;
+use scale_info::prelude::vec::Vec;
+use strum::IntoEnumIterator;
+
+/// `n` accounts that are not registered as citizens yet.
+fn citizens<T: Config>(n: u32) -> BoundedVec<T::AccountId, T::MaxCitizensPerCall> {
+	let citizens: Vec<T::AccountId> = (0..n).map(|i| account("citizen", i, 0)).collect();
+	citizens.try_into().expect("n is at most MaxCitizensPerCall")
+}
+
+/// A citizen with credit to vote in a budget election opened with `parameters`.
+fn voter<T: Config>(
+	parameters: ElectionParameters<T::Funds>,
+) -> Result<T::AccountId, BenchmarkError> {
+	let citizen: T::AccountId = account("citizen", 0, 0);
+	Voting::<T>::do_register_citizen(citizen.clone(), None)?;
+	Voting::<T>::do_open_budget(parameters)?;
+	Voting::<T>::process_credit_update(u32::MAX, u32::MAX);
+	Ok(citizen)
+}
 
 #[benchmarks]
 mod benchmarks {
 	use super::*;
 
 	#[benchmark]
-	fn do_something() {
-		let value = 100u32.into();
-		let caller: T::AccountId = whitelisted_caller();
+	fn register_citizens(
+		n: Linear<1, { T::MaxCitizensPerCall::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let origin =
+			T::RegistrarOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let citizens = citizens::<T>(n);
+
+		#[extrinsic_call]
+		_(origin as <T as frame_system::Config>::RuntimeOrigin, citizens);
+
+		assert_eq!(CitizenCount::<T>::get(), n);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn deregister_citizens(
+		n: Linear<1, { T::MaxCitizensPerCall::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let origin =
+			T::RegistrarOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let citizens = citizens::<T>(n);
+		for citizen in citizens.iter() {
+			Voting::<T>::do_register_citizen(citizen.clone(), None)?;
+		}
+
+		#[extrinsic_call]
+		_(origin as <T as frame_system::Config>::RuntimeOrigin, citizens);
+
+		assert_eq!(CitizenCount::<T>::get(), 0);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn open_budget() -> Result<(), BenchmarkError> {
+		let origin = T::ElectionAdminOrigin::try_successful_origin()
+			.map_err(|_| BenchmarkError::Weightless)?;
+
+		#[extrinsic_call]
+		_(origin as <T as frame_system::Config>::RuntimeOrigin);
+
+		assert!(Voting::<T>::budget_info().map_or(false, |budget_info| budget_info.is_open));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn close_budget() -> Result<(), BenchmarkError> {
+		let citizen: T::AccountId = account("citizen", 0, 0);
+		Voting::<T>::do_register_citizen(citizen.clone(), None)?;
+		Voting::<T>::do_open_budget(Default::default())?;
+		Voting::<T>::process_credit_update(u32::MAX, u32::MAX);
+		// The citizen votes for every department, so each of them is funded
+		for department in Department::iter() {
+			Voting::<T>::vote(RawOrigin::Signed(citizen.clone()).into(), department, 1u32.into())?;
+		}
+		let budget_info = Voting::<T>::budget_info().expect("the election was opened");
+		CurrentBudgetElection::<T>::put(BudgetInfo { is_open: false, ..budget_info.clone() });
+		let mut tally = BudgetTally::default();
+		Voting::<T>::take_ballot(&citizen, Some(&mut tally));
+		CurrentTally::<T>::put(tally);
+		PendingCreditUpdate::<T>::put(CreditUpdate {
+			budget_id: budget_info.budget_id,
+			kind: CreditUpdateKind::CountOutlays,
+			last: None,
+		});
+
+		#[block]
+		{
+			Voting::<T>::process_credit_update(0, 0);
+		}
+
+		assert!(!PendingCreditUpdate::<T>::exists());
+		Ok(())
+	}
+
+	#[benchmark]
+	fn tally_votes(v: Linear<1, { T::VotersPerBlock::get() }>) -> Result<(), BenchmarkError> {
+		let citizens: Vec<T::AccountId> = (0..v).map(|i| account("citizen", i, 0)).collect();
+		for citizen in citizens.iter() {
+			Voting::<T>::do_register_citizen(citizen.clone(), None)?;
+		}
+		Voting::<T>::do_open_budget(Default::default())?;
+		Voting::<T>::process_credit_update(u32::MAX, u32::MAX);
+		// Every voter votes for every department and proposes an outlay, so each of them has the
+		// most votes to count
+		for (i, citizen) in (0..v).zip(citizens.iter()) {
+			for department in Department::iter() {
+				Voting::<T>::vote(
+					RawOrigin::Signed(citizen.clone()).into(),
+					department,
+					1u32.into(),
+				)?;
+			}
+			OutlayProposals::<T>::insert(citizen, T::Funds::from(i));
+		}
+		let mut tally = BudgetTally::default();
+
+		#[block]
+		{
+			for citizen in citizens.iter() {
+				Voting::<T>::take_ballot(citizen, Some(&mut tally));
+			}
+		}
+
+		assert_eq!(tally.proposals, v);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn update_credit(n: Linear<1, { T::CitizensPerBlock::get() }>) -> Result<(), BenchmarkError> {
+		Voting::<T>::do_open_budget(Default::default())?;
+		for i in 0..n {
+			Voting::<T>::do_register_citizen(account("citizen", i, 0), None)?;
+		}
+		let budget_id = Voting::<T>::budget_info().expect("the election was opened").budget_id;
+		PendingCreditUpdate::<T>::put(CreditUpdate {
+			budget_id,
+			kind: CreditUpdateKind::Allocate,
+			last: None,
+		});
+
+		#[block]
+		{
+			Voting::<T>::process_credit_update(n, 0);
+		}
+
+		assert!(!PendingCreditUpdate::<T>::exists());
+		Ok(())
+	}
+
+	#[benchmark]
+	fn issue_credential() -> Result<(), BenchmarkError> {
+		let origin =
+			T::RegistrarOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let citizen: T::AccountId = account("citizen", 0, 0);
+		Voting::<T>::do_register_citizen(citizen.clone(), None)?;
+		T::Credential::revoke(&citizen)?;
+
 		#[extrinsic_call]
-		do_something(RawOrigin::Signed(caller), value);
+		_(origin as <T as frame_system::Config>::RuntimeOrigin, citizen.clone());
 
-		assert_eq!(Something::<T>::get(), Some(value));
+		assert!(T::Credential::holds(&citizen));
+		Ok(())
 	}
 
 	#[benchmark]
-	fn cause_error() {
-		Something::<T>::put(100u32);
-		let caller: T::AccountId = whitelisted_caller();
+	fn vote() -> Result<(), BenchmarkError> {
+		let citizen = voter::<T>(Default::default())?;
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(citizen.clone()), Department::Education, 1u32.into());
+
+		assert!(BudgetDistribution::<T>::contains_key(Department::Education, &citizen));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn vote_against() -> Result<(), BenchmarkError> {
+		let citizen = voter::<T>(Default::default())?;
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(citizen.clone()), Department::Education, 1u32.into());
+
+		assert!(BudgetOpposition::<T>::contains_key(Department::Education, &citizen));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn vote_with_credit() -> Result<(), BenchmarkError> {
+		let citizen = voter::<T>(ElectionParameters {
+			funding_units: FundingUnits::Fractional,
+			..Default::default()
+		})?;
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(citizen.clone()), Department::Education, 2u32.into(), true);
+
+		assert!(FractionalDistribution::<T>::contains_key(Department::Education, &citizen));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn propose_outlay() -> Result<(), BenchmarkError> {
+		let citizen = voter::<T>(ElectionParameters {
+			outlay_range: Some(OutlayRange { min: 0u32.into(), max: 100u32.into() }),
+			..Default::default()
+		})?;
+
+		#[extrinsic_call]
+		_(RawOrigin::Signed(citizen.clone()), 50u32.into());
+
+		assert!(OutlayProposals::<T>::contains_key(&citizen));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_funding_limits() -> Result<(), BenchmarkError> {
+		let origin =
+			T::TreasuryOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let cap = Perbill::from_percent(50);
+
+		#[extrinsic_call]
+		_(
+			origin as <T as frame_system::Config>::RuntimeOrigin,
+			Department::Military,
+			Perbill::zero(),
+			cap,
+		);
+
+		assert_eq!(FundingLimits::<T>::get(Department::Military).cap, cap);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn submit_legislative_allocation() -> Result<(), BenchmarkError> {
+		let origin =
+			T::TreasuryOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		Voting::<T>::do_open_budget(Default::default())?;
+		// Every department is allocated funds, replacing an allocation to every department
+		for department in Department::iter() {
+			LegislativeAllocation::<T>::insert(department, T::Funds::from(1u32));
+		}
+		let allocation: Vec<(Department, T::Funds)> =
+			Department::iter().map(|department| (department, 2u32.into())).collect();
+		let allocation: BoundedVec<_, _> =
+			allocation.try_into().map_err(|_| BenchmarkError::Weightless)?;
+
+		#[extrinsic_call]
+		_(origin as <T as frame_system::Config>::RuntimeOrigin, allocation);
+
+		assert_eq!(LegislativeAllocation::<T>::iter().count(), Department::iter().count());
+		Ok(())
+	}
+
+	#[benchmark]
+	fn issue_credentials(
+		n: Linear<1, { T::MaxCitizensPerCall::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let origin =
+			T::RegistrarOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let citizens = citizens::<T>(n);
+		for citizen in citizens.iter() {
+			Voting::<T>::do_register_citizen(citizen.clone(), None)?;
+			T::Credential::revoke(citizen)?;
+		}
+
 		#[extrinsic_call]
-		cause_error(RawOrigin::Signed(caller));
+		_(origin as <T as frame_system::Config>::RuntimeOrigin, citizens.clone());
 
-		assert_eq!(Something::<T>::get(), Some(101u32));
+		assert!(citizens.iter().all(|citizen| T::Credential::holds(citizen)));
+		Ok(())
 	}
 
 	impl_benchmark_test_suite!(Voting, crate::mock::new_test_ext(), crate::mock::Test);
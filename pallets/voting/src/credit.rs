@@ -0,0 +1,94 @@
+//! Policies deciding how much voting credit each citizen receives for a budget election.
+use crate::{Config, Pallet};
+use core::marker::PhantomData;
+use frame_support::{
+	pallet_prelude::*,
+	sp_runtime::traits::{IntegerSquareRoot, SaturatedConversion, Zero},
+};
+
+/// Decides how much voting credit each citizen receives for a budget election, so runtimes can
+/// plug in their own fairness rules.
+pub trait CreditAllocator<T: Config> {
+	/// The credit `who` receives when budget election `budget_id` opens.
+	fn allocate(who: &T::AccountId, budget_id: T::BudgetId) -> T::VotingCredit;
+
+	/// The credit `who` receives when registered while budget election `budget_id` is already
+	/// open. By default, late registrants need to wait for the next election to vote.
+	fn allocate_late(_who: &T::AccountId, _budget_id: T::BudgetId) -> T::VotingCredit {
+		Zero::zero()
+	}
+}
+
+/// Gives every citizen the same credit, as set in the governance parameters.
+pub struct FlatCredit;
+impl<T: Config> CreditAllocator<T> for FlatCredit {
+	fn allocate(_who: &T::AccountId, _budget_id: T::BudgetId) -> T::VotingCredit {
+		Pallet::<T>::parameters().given_voting_credit
+	}
+}
+
+/// Gives citizens credit following what they contributed over the fiscal period preceding the
+/// election, as described by `Weighting`.
+pub struct ContributionCredit<Weighting>(PhantomData<Weighting>);
+impl<T: Config, Weighting: Get<ContributionWeighting<T::VotingCredit>>> CreditAllocator<T>
+	for ContributionCredit<Weighting>
+{
+	fn allocate(who: &T::AccountId, budget_id: T::BudgetId) -> T::VotingCredit {
+		let ContributionWeighting { floor, cap, curve, contribution_unit } = Weighting::get();
+		let contributed: u128 = T::Contributions::contribution_of(who, budget_id).saturated_into();
+		let units = contributed.checked_div(contribution_unit).unwrap_or(0);
+		let credit = match curve {
+			ContributionCurve::Linear => units,
+			ContributionCurve::SquareRoot => units.integer_sqrt(),
+		};
+		credit.saturated_into::<T::VotingCredit>().max(floor).min(cap)
+	}
+}
+
+/// Gives citizens registered while an election is open a share of what `Inner` would have given
+/// them, proportional to the time left to vote.
+pub struct ProRataLateRegistrants<Inner>(PhantomData<Inner>);
+impl<T: Config, Inner: CreditAllocator<T>> CreditAllocator<T> for ProRataLateRegistrants<Inner> {
+	fn allocate(who: &T::AccountId, budget_id: T::BudgetId) -> T::VotingCredit {
+		Inner::allocate(who, budget_id)
+	}
+
+	fn allocate_late(who: &T::AccountId, budget_id: T::BudgetId) -> T::VotingCredit {
+		Pallet::<T>::time_left() * Inner::allocate(who, budget_id)
+	}
+}
+
+/// How `ContributionCredit` turns contributions into voting credit.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct ContributionWeighting<VotingCredit> {
+	/// The least credit a citizen receives, even if they contributed nothing.
+	pub floor: VotingCredit,
+	/// The most credit a citizen receives, however much they contributed.
+	pub cap: VotingCredit,
+	/// How contribution units translate into credit.
+	pub curve: ContributionCurve,
+	/// How much needs to be contributed to count as one unit.
+	pub contribution_unit: u128,
+}
+
+/// How contributions translate into voting credit.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum ContributionCurve {
+	/// One credit for every contribution unit.
+	Linear,
+	/// The square root of the contribution units, dampening the voice of the largest
+	/// contributors.
+	SquareRoot,
+}
+
+/// Provides what each citizen contributed through taxation.
+pub trait ContributionProvider<AccountId, BudgetId, Balance> {
+	/// What `who` contributed during the fiscal period preceding budget election `budget_id`.
+	fn contribution_of(who: &AccountId, budget_id: BudgetId) -> Balance;
+}
+
+impl<AccountId, BudgetId, Balance: Zero> ContributionProvider<AccountId, BudgetId, Balance> for () {
+	fn contribution_of(_who: &AccountId, _budget_id: BudgetId) -> Balance {
+		Zero::zero()
+	}
+}
@@ -0,0 +1,46 @@
+//! How citizenship is established and shown outside the pallet: checks on the identity of
+//! accounts claiming citizenship, so runtimes can rely on registrars they already trust instead of
+//! curating citizens themselves, and credentials issued to citizens.
+use frame_support::dispatch::DispatchResult;
+
+/// Tells whether an account's identity has been verified, e.g. through a positive judgement
+/// from a trusted registrar of the identity pallet.
+pub trait IdentityVerifier<AccountId> {
+	/// Whether `who` can claim citizenship.
+	fn is_verified(who: &AccountId) -> bool;
+}
+
+/// No account can claim citizenship.
+impl<AccountId> IdentityVerifier<AccountId> for () {
+	fn is_verified(_who: &AccountId) -> bool {
+		false
+	}
+}
+
+/// A credential held by every citizen, e.g. a non-transferable NFT, so wallets and explorers can
+/// display citizenship status. Only holders of a credential are eligible to vote.
+pub trait CitizenshipCredential<AccountId> {
+	/// Issues the credential with serial number `serial` to a newly registered citizen.
+	fn issue(who: &AccountId, serial: u32) -> DispatchResult;
+
+	/// Revokes the credential of a deregistered citizen.
+	fn revoke(who: &AccountId) -> DispatchResult;
+
+	/// Whether `who` holds a credential.
+	fn holds(who: &AccountId) -> bool;
+}
+
+/// Citizenship is only recorded in the pallet.
+impl<AccountId> CitizenshipCredential<AccountId> for () {
+	fn issue(_who: &AccountId, _serial: u32) -> DispatchResult {
+		Ok(())
+	}
+
+	fn revoke(_who: &AccountId) -> DispatchResult {
+		Ok(())
+	}
+
+	fn holds(_who: &AccountId) -> bool {
+		true
+	}
+}
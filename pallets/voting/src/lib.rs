@@ -11,22 +11,49 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod aggregation;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod credit;
+pub mod identity;
+pub mod migrations;
+pub mod weights;
+pub use aggregation::*;
+pub use credit::*;
+pub use identity::*;
+pub use weights::*;
 
 #[frame_support::pallet]
 pub mod pallet {
-	use frame_support::sp_runtime::traits::{Hash, TrailingZeroInput};
-	use frame_support::traits::fungible::{Inspect, Mutate};
-	use frame_support::traits::tokens::{Fortitude, Precision};
+	use crate::{
+		aggregation::{apportion, Aggregator, Ballot, Tally},
+		credit::{ContributionProvider, CreditAllocator},
+		identity::{CitizenshipCredential, IdentityVerifier},
+		weights::WeightInfo,
+	};
+	use frame_support::sp_runtime::traits::{
+		AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, CheckedMul, Convert, Hash,
+		MaybeSerializeDeserialize, One, SaturatedConversion, Saturating, TrailingZeroInput, Zero,
+	};
+	use frame_support::traits::fungible::{Inspect, Mutate, MutateHold};
+	use frame_support::traits::fungibles::{
+		Create as FungiblesCreate, Inspect as FungiblesInspect, Mutate as FungiblesMutate,
+	};
+	use frame_support::traits::tokens::{Fortitude, Precision, Preservation};
 	use frame_support::{
 		dispatch::{Dispatchable, GetDispatchInfo},
 		fail,
 		pallet_prelude::*,
-		traits::{fungible, fungibles},
+		storage::with_storage_layer,
+		traits::{fungible, fungibles, UnixTime},
+		sp_runtime::{
+			helpers_128bit::multiply_by_rational_with_rounding, FixedPointNumber,
+			FixedPointOperand, FixedU128, Perbill, Rounding,
+		},
+		PalletId,
 	};
 	use frame_system::pallet_prelude::*;
-	use scale_info::prelude::boxed::Box;
+	use scale_info::prelude::{boxed::Box, collections::BTreeMap, vec::Vec};
 	use strum::IntoEnumIterator;
 	use strum_macros::EnumIter;
 
@@ -34,23 +61,23 @@ pub mod pallet {
 		<T as frame_system::Config>::AccountId,
 	>>::AssetId;
 
-	type BalanceOf<T> = <<T as Config>::NativeBalance as fungible::Inspect<
+	pub(crate) type BalanceOf<T> = <<T as Config>::NativeBalance as fungible::Inspect<
 		<T as frame_system::Config>::AccountId,
 	>>::Balance;
 
+	type HashOf<T> = <T as frame_system::Config>::Hash;
+
 	type AssetBalanceOf<T> = <<T as Config>::Fungibles as fungibles::Inspect<
 		<T as frame_system::Config>::AccountId,
 	>>::Balance;
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
-	type BudgetId = u32;
-	/// Points or tokens a citizen can use to vote on a budget election.
-	type VotingCredit = u32;
-	/// Representation of how much capital is allocated to each department.
-	type Funds = u32;
-
 	#[derive(PartialEq, Eq, Clone, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
 	pub enum RawOrigin {
 		VotingSuccess,
@@ -59,19 +86,49 @@ pub mod pallet {
 	#[pallet::origin]
 	pub type Origin = RawOrigin;
 
+	/// Why the pallet holds funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// The deposit of a pending application to register as a citizen.
+		RegistrationDeposit,
+	}
+
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		/// The runtime origin type.
 		type RuntimeOrigin: From<RawOrigin>;
 
+		/// The overarching reason funds are held for.
+		type RuntimeHoldReason: From<HoldReason>;
+
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
+		/// Identifies a budget election, and the fiscal period preceding it.
+		type BudgetId: Parameter + Member + AtLeast32BitUnsigned + Copy + MaxEncodedLen;
+
+		/// Points or tokens a citizen can use to vote on a budget election.
+		type VotingCredit: Parameter
+			+ Member
+			+ AtLeast32BitUnsigned
+			+ FixedPointOperand
+			+ Copy
+			+ MaxEncodedLen;
+
+		/// Representation of how much capital is allocated to each department.
+		type Funds: Parameter
+			+ Member
+			+ AtLeast32BitUnsigned
+			+ FixedPointOperand
+			+ MaybeSerializeDeserialize
+			+ Copy
+			+ MaxEncodedLen;
+
 		/// Type to access the Balances Pallet.
 		type NativeBalance: fungible::Inspect<Self::AccountId>
 			+ fungible::Mutate<Self::AccountId>
-			+ fungible::hold::Inspect<Self::AccountId>
+			+ fungible::hold::Inspect<Self::AccountId, Reason = Self::RuntimeHoldReason>
 			+ fungible::hold::Mutate<Self::AccountId>
 			+ fungible::freeze::Inspect<Self::AccountId>
 			+ fungible::freeze::Mutate<Self::AccountId>;
@@ -81,34 +138,184 @@ pub mod pallet {
 			+ fungibles::Mutate<Self::AccountId>
 			+ fungibles::Create<Self::AccountId>;
 
+		/// The pallet's id, used to derive the account administering the assets it creates.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Decides which asset the department allocations of a budget election are issued in.
+		/// `None` keeps issuing them in the native token, so `()` can be used to opt out.
+		type BudgetAsset: Convert<Self::BudgetId, Option<AssetIdOf<Self>>>;
+
+		/// Decides which asset mirrors the voting credit of a budget election, so that citizens
+		/// can follow it from their wallets. `None` keeps the credit in this pallet's storage only.
+		/// The runtime must set this pallet as the `Freezer` of the assets pallet for the credit to
+		/// be non-transferable.
+		type CreditAsset: Convert<Self::BudgetId, Option<AssetIdOf<Self>>>;
+
+		/// The origin allowed to register and deregister citizens, e.g. a civil registry.
+		type RegistrarOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
+
+		/// The origin allowed to open and schedule budget elections, e.g. an electoral
+		/// commission.
+		type ElectionAdminOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
+
+		/// The origin allowed to set funding limits and the legislature's allocation.
+		type TreasuryOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
+
 		/// A sudo-able call.
 		type RuntimeCall: Parameter
 			+ Dispatchable<RuntimeOrigin = <Self as Config>::RuntimeOrigin>
 			+ GetDispatchInfo;
 
-		/// The amount of voting credit given to a citizen
-		type GivenVotingCredit: Get<u32>;
+		/// The amount of voting credit given to a citizen, until changed through
+		/// `set_parameters`.
+		type GivenVotingCredit: Get<Self::VotingCredit>;
+
+		/// Decides how much voting credit each citizen receives for a budget election.
+		type CreditAllocator: CreditAllocator<Self>;
+
+		/// The contributions citizens made through taxation, used by contribution-based credit.
+		type Contributions: ContributionProvider<Self::AccountId, Self::BudgetId, BalanceOf<Self>>;
 
-		/// Maximum number of possible registered users.
+		/// Maximum number of possible registered users, until changed through `set_parameters`.
 		type MaxRegisteredCitizens: Get<u32>;
 
-		/// Maximum number of budget elections the system can have.
+		/// Maximum number of budget elections the system can have, until changed through
+		/// `set_parameters`.
 		type MaxBudgetElections: Get<u32>;
 
-		/// How many number of blocks can the budget last for before being closed automatically
+		/// How many number of blocks can the budget last for before being closed automatically,
+		/// until changed through `set_parameters`.
 		type BudgetLifetime: Get<u32>;
+
+		/// Provides the current time, for budget elections following a fiscal calendar.
+		type UnixTime: UnixTime;
+
+		/// The share of a citizen's unspent credit that is carried over to the next election.
+		/// Zero disables carryover.
+		type CarryoverFraction: Get<Perbill>;
+
+		/// The most credit a citizen can carry over to the next election.
+		type MaxCarryover: Get<Self::VotingCredit>;
+
+		/// The least a department can receive once opposition votes are subtracted from its
+		/// funding.
+		type MinNetFunds: Get<Self::Funds>;
+
+		/// Decides which accounts can claim citizenship without the registrar, e.g. those with a
+		/// positive judgement from a trusted identity registrar. `()` disables claims.
+		type IdentityVerifier: IdentityVerifier<Self::AccountId>;
+
+		/// The credential issued to citizens on registration and revoked on deregistration, e.g. a
+		/// non-transferable NFT. `()` keeps citizenship in the pallet only.
+		type Credential: CitizenshipCredential<Self::AccountId>;
+
+		/// The deposit held from accounts applying to register as citizens, until the registrar
+		/// decides on their application.
+		#[pallet::constant]
+		type RegistrationDeposit: Get<BalanceOf<Self>>;
+
+		/// The most citizens that can be registered or deregistered in a single call.
+		#[pallet::constant]
+		type MaxCitizensPerCall: Get<u32>;
+
+		/// The most citizens whose credit is updated in a block when a budget election opens or
+		/// closes. The others are updated in the blocks that follow.
+		#[pallet::constant]
+		type CitizensPerBlock: Get<u32>;
+
+		/// The most voters whose ballots are counted, or cleared, in a block when a budget election
+		/// closes or is cancelled. Counting a ballot reads every department's votes.
+		#[pallet::constant]
+		type VotersPerBlock: Get<u32>;
+
+		/// Type representing the weight of this pallet
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// The citizens registered from genesis.
+		pub citizens: Vec<T::AccountId>,
+		/// The floor and cap of departments, as shares of the total outlay.
+		pub funding_limits: Vec<(Department, Perbill, Perbill)>,
+		/// If set, a budget election is open from genesis with these parameters.
+		pub budget: Option<ElectionParameters<T::Funds>>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			for citizen in &self.citizens {
+				Pallet::<T>::do_register_citizen(citizen.clone(), None)
+					.expect("genesis citizens are distinct and within the limit");
+			}
+			for (department, floor, cap) in &self.funding_limits {
+				Pallet::<T>::do_set_funding_limits(*department, *floor, *cap)
+					.expect("genesis funding limits are valid");
+			}
+			// Like any account, the treasury needs the existential deposit before taxes below it
+			// can be paid in
+			let treasury = Pallet::<T>::treasury_account_id();
+			if T::NativeBalance::balance(&treasury).is_zero() {
+				let _ = T::NativeBalance::mint_into(&treasury, T::NativeBalance::minimum_balance());
+			}
+			// Opened last, so genesis citizens receive credit
+			if let Some(parameters) = self.budget {
+				Pallet::<T>::do_open_budget(parameters)
+					.expect("genesis budget parameters are valid");
+			}
+		}
 	}
 
 	// The pallet's runtime storage items.
 	// https://docs.substrate.io/main-docs/build/runtime-storage/
+	/// The record of each registered citizen, including the credit they have left.
 	#[pallet::storage]
 	pub type RegisteredCitizens<T: Config> =
-		StorageMap<_, Blake2_128Concat, T::AccountId, VotingCredit>;
+		StorageMap<_, Blake2_128Concat, T::AccountId, CitizenInfo<T>>;
+
+	/// The serial number of the next citizenship credential to be issued.
+	#[pallet::storage]
+	pub type NextCredentialSerial<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Applications to register as a citizen the registrar has not decided on yet.
+	#[pallet::storage]
+	pub type RegistrationApplications<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, RegistrationApplication<T>>;
+
+	/// The citizens who registered themselves through `claim_citizenship`, whose identity is
+	/// checked again whenever they vote.
+	#[pallet::storage]
+	pub type ClaimedCitizens<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
+
+	/// How many citizens are registered, e.g. to compute the turnout of an election.
+	#[pallet::storage]
+	pub type CitizenCount<T: Config> = StorageValue<_, u32, ValueQuery>;
 
 	/// The information regarding the current budget election.
 	#[pallet::storage]
 	pub type CurrentBudgetElection<T: Config> = StorageValue<_, BudgetInfo<T>>;
 
+	/// The update of every citizen's credit still in progress after a budget election opened or
+	/// closed, if any.
+	#[pallet::storage]
+	pub type PendingCreditUpdate<T: Config> = StorageValue<_, CreditUpdate<T>>;
+
+	/// How many citizens have voted in the current budget election, e.g. to compute its turnout.
+	#[pallet::storage]
+	pub type VoterCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// The votes of the budget election being closed counted so far.
+	#[pallet::storage]
+	pub type CurrentTally<T: Config> = StorageValue<_, BudgetTally, ValueQuery>;
+
+	/// How many citizens proposed each total outlay in the budget election being closed. Outlays
+	/// are keyed in big-endian, so they are gone through from the lowest to find their median.
+	#[pallet::storage]
+	pub(super) type SortedOutlays<T: Config> = StorageMap<_, Identity, [u8; 16], u32, ValueQuery>;
+
 	#[pallet::storage]
 	pub type BudgetDistribution<T: Config> = StorageDoubleMap<
 		_,
@@ -116,21 +323,290 @@ pub mod pallet {
 		Department,
 		Blake2_128Concat,
 		T::AccountId, // Citizen (necessary to ensure they cannot vote the same dep twice)
-		Funds,
+		T::Funds,
+	>;
+
+	/// Funds citizens have given to each department by spending an exact amount of credit, when
+	/// fractional funding is enabled.
+	#[pallet::storage]
+	pub type FractionalDistribution<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Department,
+		Blake2_128Concat,
+		T::AccountId,
+		FixedU128,
+	>;
+
+	/// Funds citizens have taken away from each department by spending an exact amount of credit,
+	/// when fractional funding is enabled.
+	#[pallet::storage]
+	pub type FractionalOpposition<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Department,
+		Blake2_128Concat,
+		T::AccountId,
+		FixedU128,
+	>;
+
+	/// What each citizen has paid in taxes, per fiscal period. A fiscal period is identified by
+	/// the budget election it precedes.
+	#[pallet::storage]
+	pub type TaxContributions<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::BudgetId,
+		Blake2_128Concat,
+		T::AccountId,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// What all citizens together have paid in taxes, per fiscal period.
+	#[pallet::storage]
+	pub type TotalTaxContributions<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::BudgetId, BalanceOf<T>, ValueQuery>;
+
+	/// Funds citizens have voted to take away from each department.
+	#[pallet::storage]
+	pub type BudgetOpposition<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Department,
+		Blake2_128Concat,
+		T::AccountId, // Citizen (necessary to ensure they cannot vote the same dep twice)
+		T::Funds,
+	>;
+
+	/// The least and most each department can receive, as a share of the total outlay.
+	#[pallet::storage]
+	pub type FundingLimits<T: Config> =
+		StorageMap<_, Blake2_128Concat, Department, FundingLimit, ValueQuery>;
+
+	/// The allocation submitted by the legislature for the part of the current budget that is not
+	/// voted by citizens. Values are weights, relative to each other.
+	#[pallet::storage]
+	pub type LegislativeAllocation<T: Config> =
+		StorageMap<_, Blake2_128Concat, Department, T::Funds>;
+
+	/// The funds each department received from past budget elections, split between the
+	/// citizen-voted and legislature-set portions.
+	#[pallet::storage]
+	pub type BudgetHistory<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::BudgetId,
+		Blake2_128Concat,
+		Department,
+		DepartmentFunding<T::Funds>,
+	>;
+
+	/// The total outlay each citizen proposed for the current budget election, when the outlay is
+	/// on the ballot.
+	#[pallet::storage]
+	pub type OutlayProposals<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, T::Funds>;
+
+	/// Credit carried over by each citizen into a budget election, from the one before it. It is
+	/// taken into their allowance when that election opens.
+	#[pallet::storage]
+	pub type CarriedOverCredit<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::BudgetId,
+		Blake2_128Concat,
+		T::AccountId,
+		T::VotingCredit,
+		ValueQuery,
 	>;
 
+	/// How budget elections are opened automatically, if they are.
+	#[pallet::storage]
+	pub type Recurrence<T: Config> =
+		StorageValue<_, ElectionRecurrence<BlockNumberFor<T>, T::Funds>>;
+
+	/// The block the next recurring budget election opens at.
+	#[pallet::storage]
+	pub type NextElection<T: Config> = StorageValue<_, BlockNumberFor<T>>;
+
+	/// The block each budget election's results were certified at by the election admin.
+	#[pallet::storage]
+	pub type CertifiedBudgets<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::BudgetId, BlockNumberFor<T>>;
+
+	#[pallet::type_value]
+	pub fn DefaultParameters<T: Config>() -> GovernanceParameters<T::VotingCredit> {
+		GovernanceParameters {
+			given_voting_credit: T::GivenVotingCredit::get(),
+			budget_lifetime: T::BudgetLifetime::get(),
+			max_registered_citizens: T::MaxRegisteredCitizens::get(),
+			max_budget_elections: T::MaxBudgetElections::get(),
+		}
+	}
+
+	/// The governance parameters currently in force, defaulting to the ones in `Config`.
+	#[pallet::storage]
+	pub type Parameters<T: Config> =
+		StorageValue<_, GovernanceParameters<T::VotingCredit>, ValueQuery, DefaultParameters<T>>;
+
+	/// Set while the pallet burns credit, so the freeze on credit assets does not get in the way.
+	#[pallet::storage]
+	pub(super) type CreditThawed<T: Config> = StorageValue<_, bool, ValueQuery>;
+
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Carries on updating the citizens' credit if an election opened or closed recently.
+		/// Otherwise, closes recurring elections once voting is over, and opens the next one on
+		/// schedule.
+		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+			if PendingCreditUpdate::<T>::exists() {
+				return T::DbWeight::get().reads(1).saturating_add(Self::process_credit_update(
+					T::CitizensPerBlock::get(),
+					T::VotersPerBlock::get(),
+				))
+			}
+			let recurrence = match Recurrence::<T>::get() {
+				Some(recurrence) => recurrence,
+				None => return T::DbWeight::get().reads(2),
+			};
+			let mut weight = T::DbWeight::get().reads(5);
+
+			let open_budget = Self::budget_info().filter(|budget_info| budget_info.is_open);
+			if let Some(budget_info) = &open_budget {
+				if Self::past_deadline() {
+					match with_storage_layer(Self::do_close_budget) {
+						Ok(processed) => weight.saturating_accrue(processed),
+						Err(error) => {
+							// It would fail again every block, so recurring elections stop until
+							// the election admin closes or cancels the election and sets them again
+							Recurrence::<T>::kill();
+							NextElection::<T>::kill();
+							Self::deposit_event(Event::<T>::AutomaticCloseFailed {
+								budget_id: budget_info.budget_id,
+								error,
+							});
+						},
+					}
+					return weight
+				}
+			}
+
+			let next_election = NextElection::<T>::get();
+			let due = next_election.map_or(false, |at| n >= at);
+			if due && open_budget.is_none() {
+				weight.saturating_accrue(Self::open_budget_weight());
+				// A failed opening skips the cycle, rather than being retried every block
+				if let Err(error) =
+					with_storage_layer(|| Self::do_open_budget(recurrence.parameters))
+				{
+					Self::deposit_event(Event::<T>::AutomaticOpenFailed { error });
+				}
+				// An election opened late, e.g. because the last one was extended, keeps to the
+				// schedule and skips the slots already past
+				let at = next_election.unwrap_or(n);
+				let late = n.saturating_sub(at);
+				let skipped =
+					late.saturating_sub(late.checked_rem(&recurrence.period).unwrap_or(late));
+				Self::schedule_next_election(
+					at.saturating_add(recurrence.period).saturating_add(skipped),
+				);
+			}
+
+			weight
+		}
+	}
 
 	#[derive(PartialEq, Clone, DebugNoBound, Encode, Decode, TypeInfo, MaxEncodedLen)]
 	#[scale_info(skip_type_params(T))]
 	pub struct BudgetInfo<T: Config> {
-		pub budget_id: BudgetId,
+		pub budget_id: T::BudgetId,
 		/// The budget will close automatically if no citizen has done it yet, once the block has
-		/// been reached
+		/// been reached. Ignored if the budget follows a fiscal calendar.
 		pub deadline: BlockNumberFor<T>,
 		/// The current state of the budget election.
 		pub is_open: bool,
+		/// The asset the department allocations are issued in, or `None` for the native token.
+		pub asset: Option<AssetIdOf<T>>,
+		/// The asset mirroring the citizens' voting credit, if any.
+		pub credit_asset: Option<AssetIdOf<T>>,
+		/// The parameters the budget election was opened with.
+		pub parameters: ElectionParameters<T::Funds>,
+	}
+
+	/// The record of a registered citizen.
+	#[derive(PartialEq, Clone, DebugNoBound, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct CitizenInfo<T: Config> {
+		/// The voting credit the citizen has left in the current budget election.
+		pub credit: T::VotingCredit,
+		/// The block the citizen was registered at.
+		pub registered_at: BlockNumberFor<T>,
+		/// The account that registered the citizen, if the registrar was a signed origin.
+		pub registered_by: Option<T::AccountId>,
+		/// The region the citizen belongs to, e.g. their electoral district.
+		pub region: Option<u32>,
+		/// The last budget election the citizen voted in.
+		pub last_voted_budget: Option<T::BudgetId>,
+		/// Suspended citizens stay registered but cannot vote.
+		pub suspended: bool,
+	}
+
+	/// An update of every citizen's credit, going through a batch of citizens per block.
+	#[derive(PartialEq, Clone, DebugNoBound, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct CreditUpdate<T: Config> {
+		/// The budget election the credit is updated for.
+		pub budget_id: T::BudgetId,
+		/// What happens to each citizen's credit.
+		pub kind: CreditUpdateKind,
+		/// The last citizen whose credit was updated, if any.
+		pub last: Option<T::AccountId>,
+	}
+
+	/// What happens to each citizen's credit in a `CreditUpdate`.
+	#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	pub enum CreditUpdateKind {
+		/// Citizens receive their credit for an election that opened. Voting starts once all have.
+		Allocate,
+		/// The votes of an election that closed are counted and cleared. Part of the credit
+		/// citizens left unspent is carried over to the next one, and the rest is burnt.
+		Settle,
+		/// Once every citizen's votes have been counted, the outlays they proposed are gone
+		/// through to find their median, and the departments are funded.
+		CountOutlays,
+		/// The votes of an election that was cancelled are cleared, and the credit citizens were
+		/// given for it is burnt.
+		Burn,
+	}
+
+	/// The votes of a budget election counted so far, while it is being closed.
+	#[derive(
+		Clone, Default, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen,
+	)]
+	pub struct BudgetTally {
+		/// The ballots counted so far.
+		pub ballots: Tally,
+		/// Whether the votes add up to more than can be counted, in which case no department is
+		/// funded.
+		pub overflowed: bool,
+		/// How many citizens proposed a total outlay.
+		pub proposals: u32,
+		/// How many of the proposals have been gone through, from the lowest outlay.
+		pub proposals_counted: u32,
+		/// The lower median of the proposals, once it has been reached.
+		pub median_outlay: Option<u128>,
+	}
+
+	/// An application to register as a citizen.
+	#[derive(PartialEq, Clone, DebugNoBound, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	#[scale_info(skip_type_params(T))]
+	pub struct RegistrationApplication<T: Config> {
+		/// The deposit held from the applicant.
+		pub deposit: BalanceOf<T>,
+		/// The hash of documents supporting the application, if any.
+		pub evidence: Option<HashOf<T>>,
+		/// The block the application was submitted at.
+		pub submitted_at: BlockNumberFor<T>,
 	}
 
 	// Pallets use events to inform users when important changes are made.
@@ -142,12 +618,103 @@ pub mod pallet {
 		BudgetClosed,
 		/// A budget election has been opened
 		BudgetOpen,
+		/// Every citizen has received their credit for a budget election, so voting can start.
+		CreditAllocated { budget_id: T::BudgetId },
+		/// The credit citizens left unspent in a closed or cancelled budget election has been
+		/// carried over or burnt, so the next election can open.
+		CreditSettled { budget_id: T::BudgetId },
+		/// Part of the credit a citizen left unspent in a closed budget election is carried over
+		/// to the next one.
+		CreditCarriedOver { who: T::AccountId, from_budget: T::BudgetId, amount: T::VotingCredit },
+		/// The credit of a citizen could not be updated along with everyone else's.
+		CreditUpdateFailed { who: T::AccountId, error: DispatchError },
+		/// A recurring budget election could not be closed automatically. Recurring elections
+		/// stop until the election admin closes or cancels it, and sets them again.
+		AutomaticCloseFailed { budget_id: T::BudgetId, error: DispatchError },
+		/// A recurring budget election could not be opened automatically, so its cycle is skipped.
+		AutomaticOpenFailed { error: DispatchError },
+		/// The departments could not be funded once the votes of a budget election were counted.
+		/// The election is closed without funding any of them.
+		BudgetFundingFailed { budget_id: T::BudgetId, error: DispatchError },
+		/// Voting in the current budget election has been extended by the election admin.
+		BudgetExtended { budget_id: T::BudgetId },
+		/// A budget election has been cancelled by the election admin, without funding any
+		/// department.
+		BudgetCancelled { budget_id: T::BudgetId },
+		/// The results of a closed budget election have been certified by the election admin.
+		BudgetCertified { budget_id: T::BudgetId },
+		/// A new asset has been created to issue the department allocations in.
+		BudgetAssetCreated { asset: AssetIdOf<T> },
 		/// A citizen has been deregistered from the system.
 		CitizenDeregistered { who: T::AccountId },
 		/// A new citizen has been registered to vote.
 		CitizenRegistered { who: T::AccountId },
+		/// An account has applied to register as a citizen, holding a deposit.
+		RegistrationRequested { who: T::AccountId, evidence: Option<HashOf<T>> },
+		/// The registrar has approved an application, releasing its deposit.
+		RegistrationApproved { who: T::AccountId },
+		/// The registrar has rejected an application, slashing its deposit.
+		RegistrationRejected { who: T::AccountId, slashed: BalanceOf<T> },
+		/// An applicant has withdrawn their application, getting their deposit back.
+		RegistrationCancelled { who: T::AccountId },
+		/// Citizens have been registered in bulk. Those `already_registered` were skipped.
+		CitizensRegistered { registered: u32, already_registered: Vec<T::AccountId> },
+		/// Citizens have been deregistered in bulk. Those `not_found` were skipped.
+		CitizensDeregistered { deregistered: u32, not_found: Vec<T::AccountId> },
 		/// A citizen has successfully funded a department.
-		CitizenVoted { who: T::AccountId, department: Department, amount: Funds },
+		CitizenVoted { who: T::AccountId, department: Department, amount: T::Funds },
+		/// A citizen has successfully voted to defund a department.
+		CitizenOpposed { who: T::AccountId, department: Department, amount: T::Funds },
+		/// A citizen has spent `credit` on funding (`in_favour`) or defunding a department by
+		/// `funds`, its square root.
+		CitizenVotedWithCredit {
+			who: T::AccountId,
+			department: Department,
+			credit: T::VotingCredit,
+			funds: FixedU128,
+			in_favour: bool,
+		},
+		/// A citizen has proposed the total outlay of the current budget election.
+		OutlayProposed { who: T::AccountId, amount: T::Funds },
+		/// The total outlay of a closing budget election has been decided by citizens.
+		OutlayDecided { budget_id: T::BudgetId, outlay: T::Funds },
+		/// The outlay of a closing budget election has been scaled down to `pot`, all the
+		/// treasury can spend.
+		OutlayCapped { budget_id: T::BudgetId, pot: BalanceOf<T> },
+		/// The legislature has submitted its allocation for the current budget election.
+		LegislativeAllocationSubmitted,
+		/// A department has been funded by a closing budget election.
+		DepartmentFunded {
+			budget_id: T::BudgetId,
+			department: Department,
+			citizen_funds: T::Funds,
+			legislative_funds: T::Funds,
+		},
+		/// The next recurring budget election has been scheduled.
+		NextElectionScheduled { at: BlockNumberFor<T> },
+		/// Budget elections are no longer opened automatically.
+		RecurrenceCleared,
+		/// The registrar has set the region a citizen belongs to.
+		CitizenRegionSet { who: T::AccountId, region: Option<u32> },
+		/// The registrar has suspended a citizen from voting, or lifted their suspension.
+		CitizenSuspensionSet { who: T::AccountId, suspended: bool },
+		/// The governance parameters have been changed from `old` to `new`.
+		ParametersSet {
+			old: GovernanceParameters<T::VotingCredit>,
+			new: GovernanceParameters<T::VotingCredit>,
+		},
+		/// The funding limits of a department have been changed.
+		FundingLimitsSet { department: Department, floor: Perbill, cap: Perbill },
+		/// A citizen has paid taxes into the budget treasury. `total` is everything they have
+		/// paid during the fiscal `period` so far.
+		TaxPaid {
+			who: T::AccountId,
+			period: T::BudgetId,
+			amount: BalanceOf<T>,
+			total: BalanceOf<T>,
+		},
+		/// A citizen registered before citizenship credentials existed has been issued one.
+		CredentialIssued { who: T::AccountId, serial: u32 },
 	}
 
 	// Errors inform users that something went wrong.
@@ -155,7 +722,9 @@ pub mod pallet {
 	pub enum Error<T> {
 		/// A citizen is attempting to fund a department they have already voted for before
 		AlreadyVotedDepartment,
-		/// The root is trying to open the budget when it's already open
+		/// A citizen is attempting to propose the total outlay a second time.
+		AlreadyProposedOutlay,
+		/// The election admin is trying to open the budget when it's already open
 		BudgetAlreadyOpen,
 		/// A citizen is trying to vote or attempting to close a budget election when this is
 		/// already closed or doesn't exist.
@@ -164,14 +733,73 @@ pub mod pallet {
 		CannotCloseBeforeDeadline,
 		/// A citizen is trying to vote past deadline
 		CannotVotePastDeadline,
-		/// The root is trying to register a citizen that had been previously registered. They need
-		/// to be deregistered first.
+		/// A citizen is trying to vote before the opening time of the budget election.
+		VotingNotStarted,
+		/// The registrar is trying to register a citizen that had been previously registered. They
+		/// need to be deregistered first.
 		CitizenAlreadyRegistered,
 		/// A citizen is trying to cast their vote or is trying to be removed, but is not found
 		/// in the `RegisteredCitizens` storage.
 		CitizenNotRegistered,
 		/// A citizen is trying to vote but doesn't have enough voting credit left for that funding.
 		NotEnoughVotingCredit,
+		/// The registrar is trying to register a citizen when the most citizens allowed already
+		/// are.
+		TooManyCitizens,
+		/// A suspended citizen is trying to vote.
+		CitizenSuspended,
+		/// A citizen is trying to vote without holding a citizenship credential.
+		NoCitizenshipCredential,
+		/// An account is trying to apply to register as a citizen a second time.
+		AlreadyApplied,
+		/// The registrar is trying to decide on an application that does not exist.
+		ApplicationNotFound,
+		/// An account is trying to claim citizenship, or a citizen who claimed it to vote, without
+		/// a verified identity.
+		IdentityNotVerified,
+		/// The treasury is trying to set a funding floor above the cap, or floors adding up to
+		/// more than the total outlay.
+		InvalidFundingLimits,
+		/// An amount of funds, credit or a budget id does not fit in the type it is stored in.
+		ArithmeticOverflow,
+		/// The election admin is trying to open a budget whose outlay range has its minimum above
+		/// its maximum.
+		InvalidOutlayRange,
+		/// A citizen is trying to spend an exact amount of credit in a budget that only accepts
+		/// whole units of funding.
+		FractionalFundingDisabled,
+		/// The election admin is trying to open a budget whose fiscal calendar closes before it
+		/// opens, or has already closed.
+		InvalidCalendar,
+		/// The election admin is trying to schedule recurring elections without a period, or
+		/// following a fiscal calendar.
+		InvalidRecurrence,
+		/// The election admin is trying to skip or reschedule an election while none is recurring,
+		/// or to schedule one in the past.
+		CannotSchedule,
+		/// A citizen is trying to propose the total outlay of a budget that does not ask for it.
+		OutlayNotOnBallot,
+		/// A citizen is trying to propose a total outlay outside the range of the ballot.
+		OutlayOutOfRange,
+		/// The election admin is trying to extend an election by blocks when it follows a fiscal
+		/// calendar, or by time when it does not.
+		InvalidExtension,
+		/// The election admin is trying to certify a budget election that was not closed with
+		/// results, or was already certified.
+		CannotCertify,
+		/// The election admin is trying to change the governance parameters while an election is
+		/// in progress.
+		ElectionInProgress,
+		/// The election admin is trying to set a budget lifetime of zero blocks.
+		InvalidParameters,
+		/// Citizens are trying to vote, or the election admin to open or close a budget election,
+		/// while the citizens' credit is still being updated.
+		CreditUpdateInProgress,
+		/// The registrar is trying to issue a credential to a citizen who already holds one.
+		CredentialAlreadyHeld,
+		/// The election admin is trying to open a budget election when the most elections allowed
+		/// have already been held.
+		TooManyBudgetElections,
 	}
 
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
@@ -180,170 +808,1723 @@ pub mod pallet {
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::call_index(0)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		#[pallet::weight(T::WeightInfo::register_citizens(1))]
 		pub fn register_citizen(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
-			// only the root should be able to register a citizen
-			ensure_root(origin)?;
-			ensure!(
-				!RegisteredCitizens::<T>::contains_key(&who),
-				Error::<T>::CitizenAlreadyRegistered
-			);
-			// they are given 0 credits when registered. citizens must be registered before the
-			// election is open.
-			RegisteredCitizens::<T>::insert(&who, 0u32);
-			Self::deposit_event(Event::<T>::CitizenRegistered { who });
-			Ok(())
+			// only the registrar should be able to register a citizen
+			let registered_by = ensure_signed(origin.clone()).ok();
+			T::RegistrarOrigin::ensure_origin(origin)?;
+			Self::do_register_citizen(who, registered_by)
 		}
 
 		#[pallet::call_index(1)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		#[pallet::weight(T::WeightInfo::deregister_citizens(1))]
 		pub fn deregister_citizen(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
-			// only the root should be able to deregister a citizen
-			ensure_root(origin)?;
-			ensure!(RegisteredCitizens::<T>::contains_key(&who), Error::<T>::CitizenNotRegistered);
-			RegisteredCitizens::<T>::remove(&who);
-			Self::deposit_event(Event::<T>::CitizenDeregistered { who });
-			Ok(())
+			// only the registrar should be able to deregister a citizen
+			T::RegistrarOrigin::ensure_origin(origin)?;
+			Self::do_deregister_citizen(who)
 		}
 
 		/// A vote is an allocation of funds to a department using a citizen's voting credit.
 		/// Citizens need to call this extrinsic for every department they want to fund.
 		#[pallet::call_index(2)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
-		pub fn vote(origin: OriginFor<T>, department: Department, amount: Funds) -> DispatchResult {
-			// Check that the budget is marked as open
-			match CurrentBudgetElection::<T>::get() {
-				Some(budget_info) if budget_info.is_open => (),
-				_ => fail!(Error::<T>::BudgetIsClosed),
-			}
+		#[pallet::weight(T::WeightInfo::vote())]
+		pub fn vote(
+			origin: OriginFor<T>,
+			department: Department,
+			amount: T::Funds,
+		) -> DispatchResult {
+			let citizen = ensure_signed(origin)?;
+			Self::do_vote(citizen, department, amount, true)
+		}
 
-			// Check that the citizen is not trying to vote after the deadline (someone will need
-			// to manually close the budget
-			if Self::past_deadline() {
-				fail!(Error::<T>::CannotVotePastDeadline)
-			}
+		#[pallet::call_index(3)]
+		#[pallet::weight(Pallet::<T>::open_budget_weight())]
+		pub fn open_budget(origin: OriginFor<T>) -> DispatchResult {
+			// Only the election admin should be able to create a budget election.
+			T::ElectionAdminOrigin::ensure_origin(origin)?;
 
-			let citizen = ensure_signed(origin)?;
+			Self::do_open_budget(ElectionParameters::default())
+		}
 
-			let credit_available: VotingCredit = match RegisteredCitizens::<T>::get(&citizen) {
-				Some(credit) => credit,
-				_ => fail!(Error::<T>::CitizenNotRegistered),
-			};
-			let credit_needed = amount.checked_pow(2).unwrap_or(u32::MAX);
+		/// Closes the current budget election once voting is over. The votes are counted a batch
+		/// of voters per block, and the departments funded once all have been.
+		#[pallet::call_index(4)]
+		#[pallet::weight(Pallet::<T>::close_budget_weight())]
+		pub fn close_budget(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			// Anyone can call this function.
+			ensure_signed_or_root(origin)?;
 
-			// Check whether the citizen has enough credit left to vote. Subtract credit if so.
-			match credit_available.checked_sub(credit_needed) {
-				Some(vp_left) => RegisteredCitizens::<T>::set(&citizen, Some(vp_left)),
-				None => fail!(Error::<T>::NotEnoughVotingCredit),
-			}
+			let processed = Self::do_close_budget()?;
+			Ok(Some(T::DbWeight::get().reads_writes(3, 2).saturating_add(processed)).into())
+		}
 
-			// Check if a department was already funded (voted for). Otherwise update storage.
-			match BudgetDistribution::<T>::get(&department, &citizen) {
-				Some(_) => fail!(Error::<T>::AlreadyVotedDepartment),
-				None => BudgetDistribution::<T>::set(&department, &citizen, Some(amount)),
-			}
+		/// Pays taxes from a citizen's native balance into the budget treasury, recording the
+		/// contribution for the current fiscal period.
+		#[pallet::call_index(5)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(4).ref_time())]
+		pub fn pay_tax(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+			let citizen = ensure_signed(origin)?;
+			ensure!(
+				RegisteredCitizens::<T>::contains_key(&citizen),
+				Error::<T>::CitizenNotRegistered
+			);
 
-			// Deposit CitizenVote event
-			Self::deposit_event(Event::<T>::CitizenVoted { who: citizen, department, amount });
+			T::NativeBalance::transfer(
+				&citizen,
+				&Self::treasury_account_id(),
+				amount,
+				Preservation::Preserve,
+			)?;
+
+			let period = Self::current_fiscal_period();
+			let total = TaxContributions::<T>::mutate(period, &citizen, |paid| {
+				*paid = paid.saturating_add(amount);
+				*paid
+			});
+			TotalTaxContributions::<T>::mutate(period, |paid| *paid = paid.saturating_add(amount));
+
+			Self::deposit_event(Event::<T>::TaxPaid { who: citizen, period, amount, total });
 
 			Ok(())
 		}
 
-		#[pallet::call_index(3)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
-		pub fn open_budget(origin: OriginFor<T>) -> DispatchResult {
-			// Only the root should be able to create a budget election.
-			ensure_root(origin)?;
+		/// A vote against a department, taking funds away from it at the same quadratic cost as
+		/// funding it. Citizens cannot both fund and oppose the same department.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::vote_against())]
+		pub fn vote_against(
+			origin: OriginFor<T>,
+			department: Department,
+			amount: T::Funds,
+		) -> DispatchResult {
+			let citizen = ensure_signed(origin)?;
+			Self::do_vote(citizen, department, amount, false)
+		}
 
-			// Check the current budget is not already open
+		/// Sets the least (`floor`) and most (`cap`) a department can receive, as a share of the
+		/// total outlay. They are enforced when budget elections close.
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::set_funding_limits())]
+		pub fn set_funding_limits(
+			origin: OriginFor<T>,
+			department: Department,
+			floor: Perbill,
+			cap: Perbill,
+		) -> DispatchResult {
+			T::TreasuryOrigin::ensure_origin(origin)?;
+			Self::do_set_funding_limits(department, floor, cap)
+		}
+
+		/// Opens a budget election where citizens only vote `citizen_share` of the budget, and
+		/// the legislature allocates the rest.
+		#[pallet::call_index(8)]
+		#[pallet::weight(Pallet::<T>::open_budget_weight())]
+		pub fn open_budget_with_parameters(
+			origin: OriginFor<T>,
+			parameters: ElectionParameters<T::Funds>,
+		) -> DispatchResult {
+			T::ElectionAdminOrigin::ensure_origin(origin)?;
+			Self::do_open_budget(parameters)
+		}
+
+		/// Submits the legislature's allocation for the part of the current budget that is not
+		/// voted by citizens, replacing any previous one. Funds are weights, relative to each
+		/// other.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::submit_legislative_allocation())]
+		pub fn submit_legislative_allocation(
+			origin: OriginFor<T>,
+			allocation: BoundedVec<(Department, T::Funds), ConstU32<10>>,
+		) -> DispatchResult {
+			T::TreasuryOrigin::ensure_origin(origin)?;
 			match CurrentBudgetElection::<T>::get() {
-				Some(budget_info) if budget_info.is_open => fail!(Error::<T>::BudgetAlreadyOpen),
-				_ => (),
+				Some(budget_info) if budget_info.is_open => (),
+				_ => fail!(Error::<T>::BudgetIsClosed),
 			}
 
-			// Drain the accounts of all departments
-			for department in Department::iter() {
-				T::NativeBalance::set_balance(&Self::get_department_acc(department), 0u32.into());
+			let _ = LegislativeAllocation::<T>::clear(u32::MAX, None);
+			for (department, funds) in allocation {
+				LegislativeAllocation::<T>::insert(department, funds);
 			}
+			Self::deposit_event(Event::<T>::LegislativeAllocationSubmitted);
 
-			let new_id = match CurrentBudgetElection::<T>::get() {
-				Some(budget_info) => budget_info.budget_id + 1,
-				None => 0u32,
-			};
+			Ok(())
+		}
 
-			// Update current budget election
-			CurrentBudgetElection::set(Some(BudgetInfo::<T> {
-				budget_id: new_id,
-				deadline: Self::get_current_block_number() + T::BudgetLifetime::get().into(),
-				is_open: true,
-			}));
+		/// Answers the second question of the ballot: the total outlay the citizen would like
+		/// the budget to have. Only available if the budget was opened with an outlay range.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::propose_outlay())]
+		pub fn propose_outlay(origin: OriginFor<T>, amount: T::Funds) -> DispatchResult {
+			let citizen = ensure_signed(origin)?;
 
-			// Set all everyone's credit to GivenVotingCredit
-			for (citizen, _) in RegisteredCitizens::<T>::iter() {
-				RegisteredCitizens::<T>::set::<T::AccountId>(
-					citizen,
-					Some(T::GivenVotingCredit::get()),
-				);
-			}
+			let budget_info = Self::ensure_voting_open()?;
+			let citizen_info = Self::ensure_eligible(&citizen)?;
 
-			Self::deposit_event(Event::<T>::BudgetOpen);
+			let OutlayRange { min, max } =
+				budget_info.parameters.outlay_range.ok_or(Error::<T>::OutlayNotOnBallot)?;
+			ensure!(min <= amount && amount <= max, Error::<T>::OutlayOutOfRange);
+			ensure!(
+				!OutlayProposals::<T>::contains_key(&citizen),
+				Error::<T>::AlreadyProposedOutlay
+			);
+
+			OutlayProposals::<T>::insert(&citizen, amount);
+			Self::record_voter(&citizen, citizen_info, budget_info.budget_id);
+			Self::deposit_event(Event::<T>::OutlayProposed { who: citizen, amount });
 
 			Ok(())
 		}
 
-		#[pallet::call_index(4)]
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
-		pub fn close_budget(origin: OriginFor<T>) -> DispatchResult {
-			// Anyone can call this function.
-			ensure_signed_or_root(origin)?;
+		/// Spends exactly `credit` on funding (`in_favour`) or defunding a department, which
+		/// receives its square root as fractional funds. Only available if the budget was opened
+		/// with fractional funding, and lets citizens use up all their credit.
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::vote_with_credit())]
+		pub fn vote_with_credit(
+			origin: OriginFor<T>,
+			department: Department,
+			credit: T::VotingCredit,
+			in_favour: bool,
+		) -> DispatchResult {
+			let citizen = ensure_signed(origin)?;
 
-			// Ensure is past the deadline
-			if !Self::past_deadline() {
-				fail!(Error::<T>::CannotCloseBeforeDeadline)
+			let budget_info = Self::spend_credit(&citizen, department, credit)?;
+			ensure!(
+				budget_info.parameters.funding_units == FundingUnits::Fractional,
+				Error::<T>::FractionalFundingDisabled
+			);
+
+			let funds = FixedU128::checked_from_integer(credit)
+				.and_then(|credit| credit.try_sqrt())
+				.ok_or(Error::<T>::ArithmeticOverflow)?;
+			if in_favour {
+				FractionalDistribution::<T>::insert(department, &citizen, funds);
+			} else {
+				FractionalOpposition::<T>::insert(department, &citizen, funds);
 			}
 
-			// Mark budget as closed
-			match CurrentBudgetElection::<T>::get() {
-				Some(budget_info) if budget_info.is_open => {
-					let new_budget_info = BudgetInfo {
-						budget_id: budget_info.budget_id,
-						deadline: budget_info.deadline,
-						is_open: false,
-					};
-					CurrentBudgetElection::<T>::set(Some(new_budget_info))
+			Self::deposit_event(Event::<T>::CitizenVotedWithCredit {
+				who: citizen,
+				department,
+				credit,
+				funds,
+				in_favour,
+			});
+
+			Ok(())
+		}
+
+		/// Opens budget elections automatically every `period` blocks, starting at the first
+		/// block after `offset` in the cycle, and closes them once voting is over. `None` stops
+		/// recurring elections.
+		#[pallet::call_index(12)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(2).ref_time())]
+		pub fn set_recurrence(
+			origin: OriginFor<T>,
+			recurrence: Option<ElectionRecurrence<BlockNumberFor<T>, T::Funds>>,
+		) -> DispatchResult {
+			T::ElectionAdminOrigin::ensure_origin(origin)?;
+
+			let recurrence = match recurrence {
+				Some(recurrence) => recurrence,
+				None => {
+					Recurrence::<T>::kill();
+					NextElection::<T>::kill();
+					Self::deposit_event(Event::<T>::RecurrenceCleared);
+					return Ok(())
 				},
-				_ => fail!(Error::<T>::BudgetIsClosed), // Non existent is also considered close
 			};
+			ensure!(
+				!recurrence.period.is_zero() && recurrence.parameters.calendar.is_none(),
+				Error::<T>::InvalidRecurrence
+			);
 
-			// Mint funding tokens to the departments
-			for (department, _, funds) in BudgetDistribution::<T>::iter() {
-				let generated_account = Self::get_department_acc(department);
-				Self::mint_funds(&generated_account, funds.into())?;
-			}
+			// The first block after the current one that is `offset` blocks into a cycle
+			let now = Self::get_current_block_number();
+			let ElectionRecurrence { period, offset, .. } = recurrence;
+			let next = match now < offset {
+				true => offset,
+				false => now - (now - offset) % period + period,
+			};
 
-			Self::deposit_event(Event::<T>::BudgetClosed);
+			Recurrence::<T>::put(recurrence);
+			Self::schedule_next_election(next);
 
 			Ok(())
 		}
-	}
 
-	impl<T: Config> Pallet<T> {
-		pub fn get_current_block_number() -> BlockNumberFor<T> {
-			frame_system::Pallet::<T>::block_number()
+		/// Skips the next recurring election, which will be held a period later.
+		#[pallet::call_index(13)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn skip_next_election(origin: OriginFor<T>) -> DispatchResult {
+			T::ElectionAdminOrigin::ensure_origin(origin)?;
+			let (recurrence, next) = match (Recurrence::<T>::get(), NextElection::<T>::get()) {
+				(Some(recurrence), Some(next)) => (recurrence, next),
+				_ => fail!(Error::<T>::CannotSchedule),
+			};
+
+			Self::schedule_next_election(next.saturating_add(recurrence.period));
+
+			Ok(())
+		}
+
+		/// Moves the next recurring election to block `at`. The ones after it follow a period
+		/// apart.
+		#[pallet::call_index(14)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn reschedule_next_election(
+			origin: OriginFor<T>,
+			at: BlockNumberFor<T>,
+		) -> DispatchResult {
+			T::ElectionAdminOrigin::ensure_origin(origin)?;
+			ensure!(
+				Recurrence::<T>::exists() && at > Self::get_current_block_number(),
+				Error::<T>::CannotSchedule
+			);
+
+			Self::schedule_next_election(at);
+
+			Ok(())
+		}
+
+		/// Gives citizens more time to vote in the current budget election. Elections following
+		/// a fiscal calendar are extended in milliseconds, and the others in blocks.
+		#[pallet::call_index(15)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn extend_budget(
+			origin: OriginFor<T>,
+			extension: ElectionExtension<BlockNumberFor<T>>,
+		) -> DispatchResult {
+			T::ElectionAdminOrigin::ensure_origin(origin)?;
+			let mut budget_info = match CurrentBudgetElection::<T>::get() {
+				Some(budget_info) if budget_info.is_open => budget_info,
+				_ => fail!(Error::<T>::BudgetIsClosed),
+			};
+
+			match (extension, budget_info.parameters.calendar.as_mut()) {
+				(ElectionExtension::Blocks(blocks), None) =>
+					budget_info.deadline = budget_info
+						.deadline
+						.checked_add(&blocks)
+						.ok_or(Error::<T>::ArithmeticOverflow)?,
+				(ElectionExtension::Millis(millis), Some(calendar)) =>
+					calendar.closes_at = calendar
+						.closes_at
+						.checked_add(millis)
+						.ok_or(Error::<T>::ArithmeticOverflow)?,
+				_ => fail!(Error::<T>::InvalidExtension),
+			}
+
+			let budget_id = budget_info.budget_id;
+			CurrentBudgetElection::<T>::put(budget_info);
+			Self::deposit_event(Event::<T>::BudgetExtended { budget_id });
+
+			Ok(())
+		}
+
+		/// Closes the current budget election without funding any department. Unspent credit is
+		/// burnt and not carried over.
+		#[pallet::call_index(16)]
+		// Cancelling does less than closing, which also funds the departments
+		#[pallet::weight(Pallet::<T>::close_budget_weight())]
+		pub fn cancel_budget(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			T::ElectionAdminOrigin::ensure_origin(origin)?;
+			let budget_info = match CurrentBudgetElection::<T>::get() {
+				Some(budget_info) if budget_info.is_open => budget_info,
+				_ => fail!(Error::<T>::BudgetIsClosed),
+			};
+
+			let budget_id = budget_info.budget_id;
+			CurrentBudgetElection::<T>::put(BudgetInfo { is_open: false, ..budget_info });
+			Self::deposit_event(Event::<T>::BudgetCancelled { budget_id });
+
+			// Any credit still to be allocated for it is not needed anymore. The votes are
+			// cleared, and what citizens were given is burnt, a batch per block
+			let processed = Self::start_credit_update(budget_id, CreditUpdateKind::Burn);
+
+			Ok(Some(T::DbWeight::get().reads_writes(1, 1).saturating_add(processed)).into())
+		}
+
+		/// Certifies the results of a closed budget election, as recorded in `BudgetHistory`.
+		#[pallet::call_index(17)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn certify_budget(origin: OriginFor<T>, budget_id: T::BudgetId) -> DispatchResult {
+			T::ElectionAdminOrigin::ensure_origin(origin)?;
+			ensure!(
+				BudgetHistory::<T>::iter_prefix(budget_id).next().is_some() &&
+					!CertifiedBudgets::<T>::contains_key(budget_id),
+				Error::<T>::CannotCertify
+			);
+
+			CertifiedBudgets::<T>::insert(budget_id, Self::get_current_block_number());
+			Self::deposit_event(Event::<T>::BudgetCertified { budget_id });
+
+			Ok(())
+		}
+
+		/// Changes the governance parameters. They can only be changed between elections, so the
+		/// one in progress keeps the parameters it was opened with.
+		#[pallet::call_index(18)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn set_parameters(
+			origin: OriginFor<T>,
+			parameters: GovernanceParameters<T::VotingCredit>,
+		) -> DispatchResult {
+			T::ElectionAdminOrigin::ensure_origin(origin)?;
+			ensure!(
+				!Self::budget_info().map_or(false, |budget_info| budget_info.is_open),
+				Error::<T>::ElectionInProgress
+			);
+			ensure!(parameters.budget_lifetime > 0, Error::<T>::InvalidParameters);
+
+			let old = Parameters::<T>::get();
+			Parameters::<T>::put(parameters);
+			Self::deposit_event(Event::<T>::ParametersSet { old, new: parameters });
+
+			Ok(())
+		}
+
+		/// Records the region a citizen belongs to, e.g. their electoral district.
+		#[pallet::call_index(19)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn set_citizen_region(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+			region: Option<u32>,
+		) -> DispatchResult {
+			T::RegistrarOrigin::ensure_origin(origin)?;
+			RegisteredCitizens::<T>::try_mutate(&who, |citizen_info| -> DispatchResult {
+				let citizen_info = citizen_info.as_mut().ok_or(Error::<T>::CitizenNotRegistered)?;
+				citizen_info.region = region;
+				Ok(())
+			})?;
+			Self::deposit_event(Event::<T>::CitizenRegionSet { who, region });
+			Ok(())
+		}
+
+		/// Suspends a citizen from voting, or lifts their suspension. Suspended citizens keep
+		/// their record and credit.
+		#[pallet::call_index(20)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(1).ref_time())]
+		pub fn set_citizen_suspended(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+			suspended: bool,
+		) -> DispatchResult {
+			T::RegistrarOrigin::ensure_origin(origin)?;
+			RegisteredCitizens::<T>::try_mutate(&who, |citizen_info| -> DispatchResult {
+				let citizen_info = citizen_info.as_mut().ok_or(Error::<T>::CitizenNotRegistered)?;
+				citizen_info.suspended = suspended;
+				Ok(())
+			})?;
+			Self::deposit_event(Event::<T>::CitizenSuspensionSet { who, suspended });
+			Ok(())
+		}
+
+		/// Registers several citizens at once, skipping those already registered.
+		#[pallet::call_index(21)]
+		#[pallet::weight(T::WeightInfo::register_citizens(citizens.len() as u32))]
+		pub fn register_citizens(
+			origin: OriginFor<T>,
+			citizens: BoundedVec<T::AccountId, T::MaxCitizensPerCall>,
+		) -> DispatchResult {
+			let registered_by = ensure_signed(origin.clone()).ok();
+			T::RegistrarOrigin::ensure_origin(origin)?;
+
+			let mut already_registered = Vec::new();
+			let mut registered = 0u32;
+			for who in citizens {
+				if RegisteredCitizens::<T>::contains_key(&who) {
+					already_registered.push(who);
+					continue
+				}
+				Self::do_register_citizen(who, registered_by.clone())?;
+				registered += 1;
+			}
+
+			Self::deposit_event(Event::<T>::CitizensRegistered { registered, already_registered });
+			Ok(())
+		}
+
+		/// Deregisters several citizens at once, skipping those not registered.
+		#[pallet::call_index(22)]
+		#[pallet::weight(T::WeightInfo::deregister_citizens(citizens.len() as u32))]
+		pub fn deregister_citizens(
+			origin: OriginFor<T>,
+			citizens: BoundedVec<T::AccountId, T::MaxCitizensPerCall>,
+		) -> DispatchResult {
+			T::RegistrarOrigin::ensure_origin(origin)?;
+
+			let mut not_found = Vec::new();
+			let mut deregistered = 0u32;
+			for who in citizens {
+				if !RegisteredCitizens::<T>::contains_key(&who) {
+					not_found.push(who);
+					continue
+				}
+				Self::do_deregister_citizen(who)?;
+				deregistered += 1;
+			}
+
+			Self::deposit_event(Event::<T>::CitizensDeregistered { deregistered, not_found });
+			Ok(())
+		}
+
+		/// Applies to register as a citizen, holding `RegistrationDeposit` until the registrar
+		/// decides. `evidence` can be the hash of documents supporting the application.
+		#[pallet::call_index(23)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(2).ref_time())]
+		pub fn request_registration(
+			origin: OriginFor<T>,
+			evidence: Option<HashOf<T>>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				!RegisteredCitizens::<T>::contains_key(&who),
+				Error::<T>::CitizenAlreadyRegistered
+			);
+			ensure!(!RegistrationApplications::<T>::contains_key(&who), Error::<T>::AlreadyApplied);
+
+			let deposit = T::RegistrationDeposit::get();
+			T::NativeBalance::hold(&HoldReason::RegistrationDeposit.into(), &who, deposit)?;
+			RegistrationApplications::<T>::insert(
+				&who,
+				RegistrationApplication {
+					deposit,
+					evidence,
+					submitted_at: Self::get_current_block_number(),
+				},
+			);
+
+			Self::deposit_event(Event::<T>::RegistrationRequested { who, evidence });
+			Ok(())
+		}
+
+		/// Approves an application, registering the applicant and releasing their deposit.
+		/// Applicants registered in any other way also get their deposit back.
+		#[pallet::call_index(24)]
+		#[pallet::weight(
+			T::WeightInfo::register_citizens(1)
+				.saturating_add(T::DbWeight::get().reads_writes(2, 2))
+		)]
+		pub fn approve_registration(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			let registered_by = ensure_signed(origin.clone()).ok();
+			T::RegistrarOrigin::ensure_origin(origin)?;
+			ensure!(
+				RegistrationApplications::<T>::contains_key(&who),
+				Error::<T>::ApplicationNotFound
+			);
+
+			Self::do_register_citizen(who.clone(), registered_by)?;
+
+			Self::deposit_event(Event::<T>::RegistrationApproved { who });
+			Ok(())
+		}
+
+		/// Rejects an application, slashing the applicant's deposit.
+		#[pallet::call_index(25)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(2).ref_time())]
+		pub fn reject_registration(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::RegistrarOrigin::ensure_origin(origin)?;
+			let application =
+				RegistrationApplications::<T>::take(&who).ok_or(Error::<T>::ApplicationNotFound)?;
+
+			let slashed = T::NativeBalance::burn_held(
+				&HoldReason::RegistrationDeposit.into(),
+				&who,
+				application.deposit,
+				Precision::BestEffort,
+				Fortitude::Force,
+			)?;
+
+			Self::deposit_event(Event::<T>::RegistrationRejected { who, slashed });
+			Ok(())
+		}
+
+		/// Registers the caller as a citizen, as long as their identity has been verified. They
+		/// can only vote while it stays verified.
+		#[pallet::call_index(26)]
+		#[pallet::weight(
+			T::WeightInfo::register_citizens(1).saturating_add(T::DbWeight::get().reads_writes(1, 1))
+		)]
+		pub fn claim_citizenship(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(T::IdentityVerifier::is_verified(&who), Error::<T>::IdentityNotVerified);
+			Self::do_register_citizen(who.clone(), None)?;
+			ClaimedCitizens::<T>::insert(who, ());
+			Ok(())
+		}
+
+		/// Issues a credential to a citizen who does not hold one, such as those registered
+		/// before citizenship credentials existed.
+		#[pallet::call_index(27)]
+		#[pallet::weight(T::WeightInfo::issue_credential())]
+		pub fn issue_credential(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::RegistrarOrigin::ensure_origin(origin)?;
+			ensure!(RegisteredCitizens::<T>::contains_key(&who), Error::<T>::CitizenNotRegistered);
+			ensure!(!T::Credential::holds(&who), Error::<T>::CredentialAlreadyHeld);
+
+			let serial = Self::issue_next_credential(&who)?;
+			Self::deposit_event(Event::<T>::CredentialIssued { who, serial });
+			Ok(())
+		}
+
+		/// Issues credentials to several citizens at once, skipping those not registered or
+		/// already holding one. Lets the registrar catch up with every citizen registered before
+		/// citizenship credentials existed, a batch at a time.
+		#[pallet::call_index(29)]
+		#[pallet::weight(T::WeightInfo::issue_credentials(citizens.len() as u32))]
+		pub fn issue_credentials(
+			origin: OriginFor<T>,
+			citizens: BoundedVec<T::AccountId, T::MaxCitizensPerCall>,
+		) -> DispatchResult {
+			T::RegistrarOrigin::ensure_origin(origin)?;
+
+			for who in citizens {
+				if !RegisteredCitizens::<T>::contains_key(&who) || T::Credential::holds(&who) {
+					continue
+				}
+				let serial = Self::issue_next_credential(&who)?;
+				Self::deposit_event(Event::<T>::CredentialIssued { who, serial });
+			}
+			Ok(())
+		}
+
+		/// Withdraws the caller's application to register as a citizen, releasing their deposit.
+		#[pallet::call_index(28)]
+		#[pallet::weight(10_000 + T::DbWeight::get().writes(2).ref_time())]
+		pub fn cancel_registration_request(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(Self::release_application(&who)?.is_some(), Error::<T>::ApplicationNotFound);
+
+			Self::deposit_event(Event::<T>::RegistrationCancelled { who });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Registers a citizen, giving them credit if the election is already open and the credit
+		/// allocator gives some to late registrants.
+		pub(crate) fn do_register_citizen(
+			who: T::AccountId,
+			registered_by: Option<T::AccountId>,
+		) -> DispatchResult {
+			ensure!(
+				!RegisteredCitizens::<T>::contains_key(&who),
+				Error::<T>::CitizenAlreadyRegistered
+			);
+			let count = CitizenCount::<T>::get();
+			ensure!(
+				count < Self::parameters().max_registered_citizens,
+				Error::<T>::TooManyCitizens
+			);
+			// they are given 0 credits when registered, unless the election is already open and
+			// the credit allocator gives something to late registrants. Those registered before
+			// voting starts receive credit like everyone else.
+			let credit = match CurrentBudgetElection::<T>::get() {
+				Some(budget_info) if budget_info.is_open && !Self::past_deadline() => {
+					let credit = match PendingCreditUpdate::<T>::exists() {
+						true => Self::allowance(&who, budget_info.budget_id)?,
+						false => T::CreditAllocator::allocate_late(&who, budget_info.budget_id),
+					};
+					if let Some(credit_asset) = budget_info.credit_asset {
+						Self::mint_credit(credit_asset, &who, credit)?;
+					}
+					credit
+				},
+				_ => Zero::zero(),
+			};
+			RegisteredCitizens::<T>::insert(
+				&who,
+				CitizenInfo {
+					credit,
+					registered_at: Self::get_current_block_number(),
+					registered_by,
+					region: None,
+					last_voted_budget: None,
+					suspended: false,
+				},
+			);
+			CitizenCount::<T>::put(count + 1);
+			Self::release_application(&who)?;
+			Self::issue_next_credential(&who)?;
+			Self::deposit_event(Event::<T>::CitizenRegistered { who });
+			Ok(())
+		}
+
+		/// Removes an account's application to register as a citizen, if any, releasing its
+		/// deposit. Returns the application.
+		fn release_application(
+			who: &T::AccountId,
+		) -> Result<Option<RegistrationApplication<T>>, DispatchError> {
+			let application = match RegistrationApplications::<T>::take(who) {
+				Some(application) => application,
+				None => return Ok(None),
+			};
+			T::NativeBalance::release(
+				&HoldReason::RegistrationDeposit.into(),
+				who,
+				application.deposit,
+				Precision::BestEffort,
+			)?;
+			Ok(Some(application))
+		}
+
+		/// Issues a citizen a credential with the next serial number, and returns it.
+		fn issue_next_credential(who: &T::AccountId) -> Result<u32, DispatchError> {
+			let serial = NextCredentialSerial::<T>::mutate(|serial| {
+				let current = *serial;
+				serial.saturating_inc();
+				current
+			});
+			T::Credential::issue(who, serial)?;
+			Ok(serial)
+		}
+
+		/// Deregisters a citizen, burning whatever credit they have left and withdrawing the votes
+		/// they cast in the current budget election, unless they have been counted already.
+		pub(crate) fn do_deregister_citizen(who: T::AccountId) -> DispatchResult {
+			let citizen_info =
+				RegisteredCitizens::<T>::get(&who).ok_or(Error::<T>::CitizenNotRegistered)?;
+			Self::burn_remaining_credit(&who)?;
+			if let Some(budget_info) = CurrentBudgetElection::<T>::get() {
+				if citizen_info.last_voted_budget == Some(budget_info.budget_id) {
+					Self::take_ballot(&who, None);
+					if budget_info.is_open {
+						VoterCount::<T>::mutate(|voters| voters.saturating_dec());
+					}
+				}
+			}
+			RegisteredCitizens::<T>::remove(&who);
+			ClaimedCitizens::<T>::remove(&who);
+			CarriedOverCredit::<T>::remove(Self::current_fiscal_period(), &who);
+			CitizenCount::<T>::mutate(|count| count.saturating_dec());
+			T::Credential::revoke(&who)?;
+			Self::deposit_event(Event::<T>::CitizenDeregistered { who });
+			Ok(())
+		}
+
+		/// Sets the funding limits of a department, as long as they can all be met together.
+		pub(crate) fn do_set_funding_limits(
+			department: Department,
+			floor: Perbill,
+			cap: Perbill,
+		) -> DispatchResult {
+			ensure!(floor <= cap, Error::<T>::InvalidFundingLimits);
+
+			// The floors of all departments together cannot exceed the total outlay
+			let other_floors = Department::iter()
+				.filter(|other| *other != department)
+				.map(|other| FundingLimits::<T>::get(other).floor.deconstruct() as u64)
+				.sum::<u64>();
+			ensure!(
+				other_floors + floor.deconstruct() as u64 <= Perbill::one().deconstruct() as u64,
+				Error::<T>::InvalidFundingLimits
+			);
+
+			FundingLimits::<T>::insert(department, FundingLimit { floor, cap });
+			Self::deposit_event(Event::<T>::FundingLimitsSet { department, floor, cap });
+
+			Ok(())
+		}
+
+		/// Sets the block the next recurring election opens at.
+		fn schedule_next_election(at: BlockNumberFor<T>) {
+			NextElection::<T>::put(at);
+			Self::deposit_event(Event::<T>::NextElectionScheduled { at });
+		}
+
+		/// Opens a new budget election with the given parameters.
+		pub(crate) fn do_open_budget(parameters: ElectionParameters<T::Funds>) -> DispatchResult {
+			// Check the current budget is not already open
+			match CurrentBudgetElection::<T>::get() {
+				Some(budget_info) if budget_info.is_open => fail!(Error::<T>::BudgetAlreadyOpen),
+				_ => (),
+			}
+			ensure!(!PendingCreditUpdate::<T>::exists(), Error::<T>::CreditUpdateInProgress);
+			if let Some(OutlayRange { min, max }) = parameters.outlay_range {
+				ensure!(min <= max, Error::<T>::InvalidOutlayRange);
+			}
+			if let Some(FiscalCalendar { opens_at, closes_at, .. }) = parameters.calendar {
+				ensure!(
+					opens_at < closes_at && Self::now() < closes_at,
+					Error::<T>::InvalidCalendar
+				);
+			}
+
+			let new_id = match CurrentBudgetElection::<T>::get() {
+				Some(budget_info) => budget_info
+					.budget_id
+					.checked_add(&One::one())
+					.ok_or(Error::<T>::ArithmeticOverflow)?,
+				None => Zero::zero(),
+			};
+			ensure!(
+				new_id < Self::parameters().max_budget_elections.into(),
+				Error::<T>::TooManyBudgetElections
+			);
+
+			// Create the asset this budget is issued in, unless it is shared with previous ones
+			let asset = T::BudgetAsset::convert(new_id);
+			if let Some(asset) = asset.clone() {
+				if !T::Fungibles::asset_exists(asset.clone()) {
+					T::Fungibles::create(asset.clone(), Self::account_id(), true, 1u32.into())?;
+					Self::deposit_event(Event::<T>::BudgetAssetCreated { asset });
+				}
+			}
+
+			// Create the asset mirroring the credit of this budget
+			let credit_asset = T::CreditAsset::convert(new_id);
+			if let Some(credit_asset) = credit_asset.clone() {
+				if !T::Fungibles::asset_exists(credit_asset.clone()) {
+					T::Fungibles::create(
+						credit_asset.clone(),
+						Self::account_id(),
+						true,
+						1u32.into(),
+					)?;
+				}
+			}
+
+			// Votes from the previous election were cleared when it closed
+			let _ = LegislativeAllocation::<T>::clear(u32::MAX, None);
+			VoterCount::<T>::kill();
+
+			// What departments have left of their funding returns to the treasury
+			let treasury = Self::treasury_account_id();
+			for department in Department::iter() {
+				let department_acc = Self::get_department_acc(department);
+				let remainder = T::NativeBalance::reducible_balance(
+					&department_acc,
+					Preservation::Expendable,
+					Fortitude::Polite,
+				);
+				if !remainder.is_zero() {
+					T::NativeBalance::transfer(
+						&department_acc,
+						&treasury,
+						remainder,
+						Preservation::Expendable,
+					)?;
+				}
+				// A budget asset shared with previous elections is retired, and the taxes
+				// burnt to back it are minted back
+				if let Some(asset) = asset.clone() {
+					let remainder = T::Fungibles::balance(asset.clone(), &department_acc);
+					if !remainder.is_zero() {
+						T::Fungibles::burn_from(
+							asset,
+							&department_acc,
+							remainder,
+							Precision::Exact,
+							Fortitude::Force,
+						)?;
+						T::NativeBalance::mint_into(&treasury, remainder)?;
+					}
+				}
+			}
+
+			// Update current budget election
+			let deadline = Self::get_current_block_number()
+				.checked_add(&Self::parameters().budget_lifetime.into())
+				.ok_or(Error::<T>::ArithmeticOverflow)?;
+			CurrentBudgetElection::set(Some(BudgetInfo::<T> {
+				budget_id: new_id,
+				deadline,
+				is_open: true,
+				asset,
+				credit_asset: credit_asset.clone(),
+				parameters,
+			}));
+
+			Self::deposit_event(Event::<T>::BudgetOpen);
+
+			// Set everyone's credit according to the credit allocator, a batch per block
+			Self::start_credit_update(new_id, CreditUpdateKind::Allocate);
+
+			Ok(())
+		}
+
+		/// Closes the current budget election once voting is over, funding the departments.
+		/// Closes the current budget election, and starts counting its votes and settling the
+		/// credit of its voters. Returns the weight of the first batch of citizens.
+		pub(crate) fn do_close_budget() -> Result<Weight, DispatchError> {
+			// Ensure is past the deadline
+			if !Self::past_deadline() {
+				fail!(Error::<T>::CannotCloseBeforeDeadline)
+			}
+			ensure!(!PendingCreditUpdate::<T>::exists(), Error::<T>::CreditUpdateInProgress);
+
+			// Mark budget as closed
+			let budget_info = match CurrentBudgetElection::<T>::get() {
+				Some(budget_info) if budget_info.is_open => {
+					let new_budget_info = BudgetInfo { is_open: false, ..budget_info };
+					CurrentBudgetElection::<T>::set(Some(new_budget_info.clone()));
+					new_budget_info
+				},
+				_ => fail!(Error::<T>::BudgetIsClosed), // Non existent is also considered close
+			};
+
+			// The votes are counted, and only part of the unspent credit can be used in the next
+			// election
+			Ok(Self::start_credit_update(budget_info.budget_id, CreditUpdateKind::Settle))
+		}
+
+		/// Funds the departments according to the votes counted in `tally`, once they all have
+		/// been. Failing to leaves the election closed without funding any department.
+		fn fund_budget(tally: BudgetTally) {
+			let budget_info = match CurrentBudgetElection::<T>::get() {
+				Some(budget_info) => budget_info,
+				None => return,
+			};
+			if let Err(error) = with_storage_layer(|| Self::do_fund_budget(&budget_info, &tally)) {
+				Self::deposit_event(Event::<T>::BudgetFundingFailed {
+					budget_id: budget_info.budget_id,
+					error,
+				});
+			}
+			Self::deposit_event(Event::<T>::BudgetClosed);
+		}
+
+		/// Pays each department its funding out of the treasury, and records it.
+		fn do_fund_budget(budget_info: &BudgetInfo<T>, tally: &BudgetTally) -> DispatchResult {
+			ensure!(!tally.overflowed, Error::<T>::ArithmeticOverflow);
+			let median_outlay =
+				tally.median_outlay.map(Self::checked_convert::<_, T::Funds>).transpose()?;
+			if let Some(outlay) = median_outlay {
+				Self::deposit_event(Event::<T>::OutlayDecided {
+					budget_id: budget_info.budget_id,
+					outlay,
+				});
+			}
+
+			// Pay the departments out of the taxes held by the treasury
+			let outcome = Self::capped_by_treasury(
+				budget_info.budget_id,
+				Self::hybrid_outcome(budget_info.parameters, &tally.ballots, median_outlay)?,
+				budget_info.asset.is_none(),
+			)?;
+			let treasury = Self::treasury_account_id();
+			for (department, funding) in outcome {
+				BudgetHistory::<T>::insert(budget_info.budget_id, department, funding);
+				Self::deposit_event(Event::<T>::DepartmentFunded {
+					budget_id: budget_info.budget_id,
+					department,
+					citizen_funds: funding.citizen,
+					legislative_funds: funding.legislative,
+				});
+
+				let funds = funding
+					.citizen
+					.checked_add(&funding.legislative)
+					.ok_or(Error::<T>::ArithmeticOverflow)?;
+				if funds.is_zero() {
+					continue
+				}
+				let generated_account = Self::get_department_acc(department);
+				let amount: BalanceOf<T> = Self::checked_convert(funds)?;
+				match budget_info.asset.clone() {
+					// The budget asset issued to the department is backed by the taxes it burns
+					Some(asset) => {
+						T::NativeBalance::burn_from(
+							&treasury,
+							amount,
+							Precision::Exact,
+							Fortitude::Polite,
+						)?;
+						Self::mint_asset_funds(
+							asset,
+							&generated_account,
+							Self::checked_convert(funds)?,
+						)?;
+					},
+					None => {
+						T::NativeBalance::transfer(
+							&treasury,
+							&generated_account,
+							amount,
+							Preservation::Preserve,
+						)?;
+					},
+				}
+			}
+			Ok(())
+		}
+
+		/// Scales the funding of every department down, so the outlay does not exceed what the
+		/// treasury can spend. When budgets are paid in the native token, funding too small for a
+		/// department's account to exist stays in the treasury.
+		fn capped_by_treasury(
+			budget_id: T::BudgetId,
+			outcome: Vec<(Department, DepartmentFunding<T::Funds>)>,
+			native: bool,
+		) -> Result<Vec<(Department, DepartmentFunding<T::Funds>)>, DispatchError> {
+			let pot = T::NativeBalance::reducible_balance(
+				&Self::treasury_account_id(),
+				Preservation::Preserve,
+				Fortitude::Polite,
+			);
+			let wide_pot: u128 = Self::checked_convert(pot)?;
+			let mut outlay: u128 = 0;
+			for (_, funding) in outcome.iter() {
+				for funds in [funding.citizen, funding.legislative] {
+					outlay = outlay
+						.checked_add(Self::checked_convert(funds)?)
+						.ok_or(Error::<T>::ArithmeticOverflow)?;
+				}
+			}
+			let capped = outlay > wide_pot;
+			if capped {
+				Self::deposit_event(Event::<T>::OutlayCapped { budget_id, pot });
+			}
+			let scale = |funds: T::Funds| -> Result<T::Funds, DispatchError> {
+				if !capped {
+					return Ok(funds)
+				}
+				let funds = multiply_by_rational_with_rounding(
+					Self::checked_convert(funds)?,
+					wide_pot,
+					outlay,
+					Rounding::Down,
+				)
+				.ok_or(Error::<T>::ArithmeticOverflow)?;
+				Ok(Self::checked_convert(funds)?)
+			};
+			let minimum: u128 = Self::checked_convert(T::NativeBalance::minimum_balance())?;
+
+			let mut capped_outcome = Vec::new();
+			for (department, funding) in outcome {
+				let mut funding = DepartmentFunding {
+					citizen: scale(funding.citizen)?,
+					legislative: scale(funding.legislative)?,
+				};
+				let funds = Self::checked_convert::<_, u128>(funding.citizen)?
+					.checked_add(Self::checked_convert(funding.legislative)?)
+					.ok_or(Error::<T>::ArithmeticOverflow)?;
+				if native && funds < minimum {
+					funding = Default::default();
+				}
+				capped_outcome.push((department, funding));
+			}
+			Ok(capped_outcome)
+		}
+
+		/// Starts updating every citizen's credit for budget election `budget_id`, replacing any
+		/// update in progress, and goes through the first batch of citizens straight away.
+		/// Returns the weight of that batch.
+		fn start_credit_update(budget_id: T::BudgetId, kind: CreditUpdateKind) -> Weight {
+			PendingCreditUpdate::<T>::put(CreditUpdate { budget_id, kind, last: None });
+			CurrentTally::<T>::kill();
+			Self::process_credit_update(T::CitizensPerBlock::get(), T::VotersPerBlock::get())
+		}
+
+		/// Updates the credit of up to `limit` more citizens in the pending credit update,
+		/// counting or clearing the ballots of at most `voter_limit` of them, and finishes it once
+		/// every citizen's has been. Returns the weight of what was done.
+		pub(crate) fn process_credit_update(limit: u32, voter_limit: u32) -> Weight {
+			let mut update = match PendingCreditUpdate::<T>::get() {
+				Some(update) => update,
+				None => return Weight::zero(),
+			};
+			let mut tally = CurrentTally::<T>::get();
+			let (mut updated, mut counted) = (0, 0);
+
+			if update.kind != CreditUpdateKind::CountOutlays {
+				let mut citizens = match &update.last {
+					Some(last) => RegisteredCitizens::<T>::iter_from(
+						RegisteredCitizens::<T>::hashed_key_for(last),
+					),
+					None => RegisteredCitizens::<T>::iter(),
+				};
+				let gone_through = loop {
+					let (citizen, citizen_info) = match citizens.next() {
+						Some(next) => next,
+						None => break true,
+					};
+					let voted = update.kind != CreditUpdateKind::Allocate &&
+						citizen_info.last_voted_budget == Some(update.budget_id);
+					if updated == limit || (voted && counted == voter_limit) {
+						break false
+					}
+					if voted {
+						let tally = match update.kind {
+							CreditUpdateKind::Settle => Some(&mut tally),
+							_ => None,
+						};
+						Self::take_ballot(&citizen, tally);
+						counted += 1;
+					}
+					// A citizen whose credit cannot be updated does not hold everyone else back
+					let result = with_storage_layer(|| {
+						Self::update_citizen_credit(&citizen, citizen_info, &update)
+					});
+					if let Err(error) = result {
+						Self::deposit_event(Event::<T>::CreditUpdateFailed {
+							who: citizen.clone(),
+							error,
+						});
+					}
+					update.last = Some(citizen);
+					updated += 1;
+				};
+
+				let mut weight = T::DbWeight::get()
+					.reads_writes(2, 2)
+					.saturating_add(T::WeightInfo::update_credit(updated))
+					.saturating_add(T::WeightInfo::tally_votes(counted));
+				if !gone_through {
+					PendingCreditUpdate::<T>::put(update);
+					CurrentTally::<T>::put(tally);
+					return weight
+				}
+				let budget_id = update.budget_id;
+				match update.kind {
+					CreditUpdateKind::Allocate => {
+						PendingCreditUpdate::<T>::kill();
+						Self::deposit_event(Event::<T>::CreditAllocated { budget_id });
+						return weight
+					},
+					CreditUpdateKind::Burn => {
+						PendingCreditUpdate::<T>::kill();
+						Self::deposit_event(Event::<T>::CreditSettled { budget_id });
+						return weight
+					},
+					_ => {
+						Self::deposit_event(Event::<T>::CreditSettled { budget_id });
+						update = CreditUpdate {
+							budget_id,
+							kind: CreditUpdateKind::CountOutlays,
+							last: None,
+						};
+					},
+				}
+				let outlay_limit = voter_limit.saturating_sub(counted);
+				weight.saturating_accrue(Self::count_outlays(&mut tally, outlay_limit));
+				return Self::finish_tally(update, tally, weight)
+			}
+
+			let weight = T::DbWeight::get()
+				.reads_writes(2, 2)
+				.saturating_add(Self::count_outlays(&mut tally, voter_limit));
+			Self::finish_tally(update, tally, weight)
+		}
+
+		/// Goes through up to `limit` more of the proposed outlays, from the lowest, until their
+		/// median is reached and they are all cleared. Returns the weight of what was done.
+		fn count_outlays(tally: &mut BudgetTally, limit: u32) -> Weight {
+			let median = tally.proposals.saturating_sub(1) / 2;
+			let mut outlays = SortedOutlays::<T>::drain();
+			let mut counted = 0;
+			while counted < limit {
+				let (outlay, proposals) = match outlays.next() {
+					Some(next) => next,
+					None => break,
+				};
+				let proposals_counted = tally.proposals_counted.saturating_add(proposals);
+				if tally.median_outlay.is_none() && proposals_counted > median {
+					tally.median_outlay = Some(u128::from_be_bytes(outlay));
+				}
+				tally.proposals_counted = proposals_counted;
+				counted += 1;
+			}
+			T::WeightInfo::tally_votes(counted)
+		}
+
+		/// Funds the departments once every proposed outlay has been gone through, and otherwise
+		/// saves how far the tally got.
+		fn finish_tally(update: CreditUpdate<T>, tally: BudgetTally, weight: Weight) -> Weight {
+			if tally.proposals_counted < tally.proposals {
+				PendingCreditUpdate::<T>::put(update);
+				CurrentTally::<T>::put(tally);
+				return weight
+			}
+			PendingCreditUpdate::<T>::kill();
+			CurrentTally::<T>::kill();
+			Self::fund_budget(tally);
+			weight.saturating_add(T::WeightInfo::close_budget())
+		}
+
+		/// Takes the votes and outlay `citizen` cast in the current budget election off the ballot,
+		/// counting them in `tally` if any.
+		pub(crate) fn take_ballot(citizen: &T::AccountId, tally: Option<&mut BudgetTally>) {
+			let mut ballot: Ballot = Department::iter().map(|_| 0).collect();
+			let mut overflowed = false;
+			for (index, department) in Department::iter().enumerate() {
+				let votes = [
+					BudgetDistribution::<T>::take(department, citizen).map(Self::whole_votes),
+					FractionalDistribution::<T>::take(department, citizen)
+						.map(Self::fractional_votes),
+					BudgetOpposition::<T>::take(department, citizen)
+						.map(|funds| Self::whole_votes(funds).map(|votes| -votes)),
+					FractionalOpposition::<T>::take(department, citizen)
+						.map(|funds| Self::fractional_votes(funds).map(|votes| -votes)),
+				];
+				for votes in votes.into_iter().flatten() {
+					match votes.and_then(|votes| ballot[index].checked_add(votes)) {
+						Some(votes) => ballot[index] = votes,
+						None => overflowed = true,
+					}
+				}
+			}
+			let outlay = OutlayProposals::<T>::take(citizen);
+
+			if let Some(tally) = tally {
+				tally.overflowed |= overflowed || tally.ballots.add(&ballot).is_none();
+				if let Some(outlay) = outlay {
+					match Self::checked_convert::<_, u128>(outlay) {
+						Ok(outlay) => {
+							SortedOutlays::<T>::mutate(outlay.to_be_bytes(), |proposals| {
+								proposals.saturating_inc()
+							});
+							tally.proposals.saturating_inc();
+						},
+						Err(_) => tally.overflowed = true,
+					}
+				}
+			}
+		}
+
+		/// The votes `funds` of whole funding amount to, with the precision of `FixedU128`.
+		fn whole_votes(funds: T::Funds) -> Option<i128> {
+			FixedU128::checked_from_integer(funds).and_then(Self::fractional_votes)
+		}
+
+		/// The votes fractional funding amounts to.
+		fn fractional_votes(funds: FixedU128) -> Option<i128> {
+			i128::try_from(funds.into_inner()).ok()
+		}
+
+		/// Updates the credit of a single citizen, as part of `update`.
+		fn update_citizen_credit(
+			citizen: &T::AccountId,
+			citizen_info: CitizenInfo<T>,
+			update: &CreditUpdate<T>,
+		) -> DispatchResult {
+			match update.kind {
+				CreditUpdateKind::Allocate => {
+					let credit = Self::allowance(citizen, update.budget_id)?;
+					// Registering before voting started may have given them credit already
+					Self::burn_remaining_credit(citizen)?;
+					if let Some(credit_asset) =
+						Self::budget_info().and_then(|budget_info| budget_info.credit_asset)
+					{
+						Self::mint_credit(credit_asset, citizen, credit)?;
+					}
+					RegisteredCitizens::<T>::insert(
+						citizen,
+						CitizenInfo { credit, ..citizen_info },
+					);
+				},
+				CreditUpdateKind::Settle => {
+					let carried = (T::CarryoverFraction::get() * citizen_info.credit)
+						.min(T::MaxCarryover::get());
+					if !carried.is_zero() {
+						let next_id = update
+							.budget_id
+							.checked_add(&One::one())
+							.ok_or(Error::<T>::ArithmeticOverflow)?;
+						CarriedOverCredit::<T>::insert(next_id, citizen, carried);
+						Self::deposit_event(Event::<T>::CreditCarriedOver {
+							who: citizen.clone(),
+							from_budget: update.budget_id,
+							amount: carried,
+						});
+					}
+					Self::burn_remaining_credit(citizen)?;
+				},
+				CreditUpdateKind::Burn => Self::burn_remaining_credit(citizen)?,
+				// Every citizen has been gone through before the outlays are counted
+				CreditUpdateKind::CountOutlays => (),
+			}
+			Ok(())
+		}
+
+		/// The credit a citizen receives for budget election `budget_id`, including what they
+		/// carried over from the one before it.
+		fn allowance(
+			citizen: &T::AccountId,
+			budget_id: T::BudgetId,
+		) -> Result<T::VotingCredit, DispatchError> {
+			T::CreditAllocator::allocate(citizen, budget_id)
+				.checked_add(&CarriedOverCredit::<T>::take(budget_id, citizen))
+				.ok_or(Error::<T>::ArithmeticOverflow.into())
+		}
+
+		/// Records that a citizen voted in budget election `budget_id`, along with the rest of
+		/// their updated record.
+		fn record_voter(
+			citizen: &T::AccountId,
+			citizen_info: CitizenInfo<T>,
+			budget_id: T::BudgetId,
+		) {
+			if citizen_info.last_voted_budget != Some(budget_id) {
+				VoterCount::<T>::mutate(|voters| voters.saturating_inc());
+			}
+			RegisteredCitizens::<T>::insert(
+				citizen,
+				CitizenInfo { last_voted_budget: Some(budget_id), ..citizen_info },
+			);
+		}
+
+		/// The most opening a budget election can weigh, including the credit of the first
+		/// batch of citizens.
+		pub(crate) fn open_budget_weight() -> Weight {
+			T::WeightInfo::open_budget()
+				.saturating_add(T::WeightInfo::update_credit(T::CitizensPerBlock::get()))
+		}
+
+		/// The most closing a budget election can weigh, including counting the votes and
+		/// settling the credit of the first batch of citizens, and funding the departments if
+		/// they are all.
+		pub(crate) fn close_budget_weight() -> Weight {
+			T::DbWeight::get()
+				.reads_writes(5, 4)
+				.saturating_add(T::WeightInfo::update_credit(T::CitizensPerBlock::get()))
+				.saturating_add(T::WeightInfo::tally_votes(T::VotersPerBlock::get()))
+				.saturating_add(T::WeightInfo::close_budget())
+		}
+
+		/// Spends a citizen's credit on funding (`in_favour`) or defunding a department.
+		fn do_vote(
+			citizen: T::AccountId,
+			department: Department,
+			amount: T::Funds,
+			in_favour: bool,
+		) -> DispatchResult {
+			// Funding costs the square of its amount in credit
+			let amount_credit: T::VotingCredit = Self::checked_convert(amount)?;
+			let credit_needed = amount_credit
+				.checked_mul(&amount_credit)
+				.ok_or(Error::<T>::ArithmeticOverflow)?;
+			Self::spend_credit(&citizen, department, credit_needed)?;
+
+			if in_favour {
+				BudgetDistribution::<T>::set(&department, &citizen, Some(amount));
+			} else {
+				BudgetOpposition::<T>::set(&department, &citizen, Some(amount));
+			}
+
+			Self::deposit_event(match in_favour {
+				true => Event::<T>::CitizenVoted { who: citizen, department, amount },
+				false => Event::<T>::CitizenOpposed { who: citizen, department, amount },
+			});
+
+			Ok(())
+		}
+
+		/// Takes `credit_needed` from a citizen voting on a department, as long as they can still
+		/// vote on it. Returns the budget election they are voting in.
+		fn spend_credit(
+			citizen: &T::AccountId,
+			department: Department,
+			credit_needed: T::VotingCredit,
+		) -> Result<BudgetInfo<T>, DispatchError> {
+			let budget_info = Self::ensure_voting_open()?;
+
+			let citizen_info = Self::ensure_eligible(citizen)?;
+
+			// Check whether the citizen has enough credit left to vote. Subtract credit if so.
+			match citizen_info.credit.checked_sub(credit_needed) {
+				Some(vp_left) => Self::record_voter(
+					citizen,
+					CitizenInfo { credit: vp_left, ..citizen_info },
+					budget_info.budget_id,
+				),
+				None => fail!(Error::<T>::NotEnoughVotingCredit),
+			}
+
+			// Check if a department was already voted for or against
+			if BudgetDistribution::<T>::contains_key(department, citizen) ||
+				BudgetOpposition::<T>::contains_key(department, citizen) ||
+				FractionalDistribution::<T>::contains_key(department, citizen) ||
+				FractionalOpposition::<T>::contains_key(department, citizen)
+			{
+				fail!(Error::<T>::AlreadyVotedDepartment)
+			}
+
+			// Burn the spent credit from the citizen's credit asset
+			if let Some(credit_asset) = budget_info.credit_asset.clone() {
+				Self::burn_credit(credit_asset, citizen, Self::checked_convert(credit_needed)?)?;
+			}
+
+			Ok(budget_info)
+		}
+
+		/// The funds voted for and against a department in the current budget election.
+		pub fn tally(department: Department) -> (FixedU128, FixedU128) {
+			let whole = |funds: T::Funds| FixedU128::saturating_from_integer(funds);
+			let sum = |total: FixedU128, funds: FixedU128| total.saturating_add(funds);
+			let support = BudgetDistribution::<T>::iter_prefix_values(department)
+				.map(whole)
+				.chain(FractionalDistribution::<T>::iter_prefix_values(department))
+				.fold(FixedU128::zero(), sum);
+			let opposition = BudgetOpposition::<T>::iter_prefix_values(department)
+				.map(whole)
+				.chain(FractionalOpposition::<T>::iter_prefix_values(department))
+				.fold(FixedU128::zero(), sum);
+			(support, opposition)
+		}
+
+		/// The votes of each citizen in the current budget election, in `Department` order. Fails
+		/// with `ArithmeticOverflow` if a citizen's votes do not fit in a `Ballot`.
+		pub fn ballots() -> Result<Vec<Ballot>, Error<T>> {
+			let departments: Vec<Department> = Department::iter().collect();
+			let mut ballots: BTreeMap<T::AccountId, Ballot> = BTreeMap::new();
+			let (whole, fractional) = (Self::whole_votes, Self::fractional_votes);
+			for (index, department) in departments.iter().enumerate() {
+				let support = BudgetDistribution::<T>::iter_prefix(department)
+					.map(|(citizen, funds)| (citizen, whole(funds)))
+					.chain(
+						FractionalDistribution::<T>::iter_prefix(department)
+							.map(|(citizen, funds)| (citizen, fractional(funds))),
+					);
+				let opposition =
+					BudgetOpposition::<T>::iter_prefix(department)
+						.map(|(citizen, funds)| (citizen, whole(funds).map(|votes| -votes)))
+						.chain(FractionalOpposition::<T>::iter_prefix(department).map(
+							|(citizen, funds)| (citizen, fractional(funds).map(|votes| -votes)),
+						));
+				for (citizen, votes) in support.chain(opposition) {
+					let ballot = ballots
+						.entry(citizen)
+						.or_insert_with(|| departments.iter().map(|_| 0).collect());
+					ballot[index] = votes
+						.and_then(|votes| ballot[index].checked_add(votes))
+						.ok_or(Error::<T>::ArithmeticOverflow)?;
+				}
+			}
+			Ok(ballots.into_values().collect())
+		}
+
+		/// The funds each department receives from the current budget election according to
+		/// `aggregator`, before funding limits are enforced. With `Sum`, each department receives
+		/// the whole units of funding it was voted. Other rules share the whole units of the
+		/// outlay, the sum of all votes, by the largest remainder method. No department goes below
+		/// `MinNetFunds`. Fails with `ArithmeticOverflow` if the votes add up to more than fits in
+		/// `T::Funds`.
+		pub fn aggregated_funds(
+			aggregator: Aggregator,
+		) -> Result<Vec<(Department, T::Funds)>, Error<T>> {
+			let mut tally = Tally::default();
+			for ballot in Self::ballots()? {
+				tally.add(&ballot).ok_or(Error::<T>::ArithmeticOverflow)?;
+			}
+			Self::funds_of(&tally, aggregator)
+		}
+
+		/// The funds each department receives according to the ballots counted in `tally`, as
+		/// in `aggregated_funds`.
+		fn funds_of(
+			tally: &Tally,
+			aggregator: Aggregator,
+		) -> Result<Vec<(Department, T::Funds)>, Error<T>> {
+			let departments = Department::iter().count();
+			let votes = tally.weights(Aggregator::Sum, departments);
+			let funds: Vec<u128> = match aggregator {
+				Aggregator::Sum => votes.iter().map(|votes| votes / FixedU128::DIV).collect(),
+				_ => {
+					let outlay = Self::checked_sum(votes)? / FixedU128::DIV;
+					apportion(outlay, &tally.weights(aggregator, departments))
+						.ok_or(Error::<T>::ArithmeticOverflow)?
+				},
+			};
+
+			Department::iter()
+				.zip(funds)
+				.map(|(department, funds)| {
+					let funds: T::Funds = Self::checked_convert(funds)?;
+					Ok((department, funds.max(T::MinNetFunds::get())))
+				})
+				.collect()
+		}
+
+		/// The funds each department receives according to the ballots counted in `tally`, once
+		/// the funding limits have been enforced.
+		pub fn budget_outcome(
+			tally: &Tally,
+			aggregator: Aggregator,
+		) -> Result<Vec<(Department, T::Funds)>, Error<T>> {
+			let votes = Self::funds_of(tally, aggregator)?
+				.into_iter()
+				.map(|(department, funds)| Ok((department, Self::checked_convert(funds)?)))
+				.collect::<Result<Vec<(Department, u128)>, Error<T>>>()?;
+			Self::within_funding_limits(votes)?
+				.into_iter()
+				.map(|(department, funds)| Ok((department, Self::checked_convert(funds)?)))
+				.collect()
+		}
+
+		/// Enforces the funding limits on `votes`, the funds each department would receive
+		/// without them.
+		///
+		/// Departments pushed over their cap or under their floor are fixed there, and what is
+		/// left of the total outlay is shared among the others proportionally to their votes,
+		/// until no limit is violated.
+		fn within_funding_limits(
+			votes: Vec<(Department, u128)>,
+		) -> Result<Vec<(Department, u128)>, Error<T>> {
+			let total = Self::checked_sum(votes.iter().map(|(_, funds)| *funds))?;
+			let mut fixed: Vec<Option<u128>> = votes.iter().map(|_| None).collect();
+
+			loop {
+				let fixed_total = Self::checked_sum(fixed.iter().flatten().copied())?;
+				let free_total = total.saturating_sub(fixed_total);
+				let free_votes = Self::checked_sum(
+					votes
+						.iter()
+						.zip(fixed.iter())
+						.filter(|(_, fixed)| fixed.is_none())
+						.map(|((_, funds), _)| *funds),
+				)?;
+				let share = |funds: u128| match free_votes {
+					0 => Ok(0),
+					_ => multiply_by_rational_with_rounding(
+						free_total,
+						funds,
+						free_votes,
+						Rounding::Down,
+					)
+					.ok_or(Error::<T>::ArithmeticOverflow),
+				};
+
+				let mut violated = false;
+				for ((department, funds), fixed) in votes.iter().zip(fixed.iter_mut()) {
+					if fixed.is_some() {
+						continue
+					}
+					let FundingLimit { floor, cap } = FundingLimits::<T>::get(department);
+					let (floor, cap) = (floor * total, cap * total);
+					let allocated = share(*funds)?;
+					if allocated < floor {
+						*fixed = Some(floor);
+						violated = true;
+					} else if allocated > cap {
+						*fixed = Some(cap);
+						violated = true;
+					}
+				}
+
+				if !violated {
+					return votes
+						.iter()
+						.zip(fixed.iter())
+						.map(|((department, funds), fixed)| {
+							Ok((*department, fixed.map_or_else(|| share(*funds), Ok)?))
+						})
+						.collect()
+				}
+			}
+		}
+
+		/// The median of the total outlays proposed by citizens in the current budget election, if
+		/// any. The lower median is taken, so no citizen can drag the outlay towards their proposal
+		/// by exaggerating it.
+		pub fn median_outlay() -> Option<T::Funds> {
+			let mut proposals: Vec<T::Funds> = OutlayProposals::<T>::iter_values().collect();
+			if proposals.is_empty() {
+				return None
+			}
+			proposals.sort_unstable();
+			Some(proposals[(proposals.len() - 1) / 2])
+		}
+
+		/// The funds each department receives according to the ballots counted in `tally`.
+		/// Citizens decide how `citizen_share` of the outlay is distributed, and the legislature
+		/// the rest. If the legislature has not submitted an allocation, its share is left
+		/// unallocated.
+		///
+		/// The outlay is `median_outlay`, the median of the citizens' proposals, when it is on the
+		/// ballot, and the sum of all votes otherwise. If no citizen voted, it is the sum of the
+		/// legislature's allocation.
+		///
+		/// The funding limits apply to what each department receives from both together. The
+		/// funding of a department moved by them is split between citizens and the legislature
+		/// in the same proportion as before.
+		pub fn hybrid_outcome(
+			parameters: ElectionParameters<T::Funds>,
+			tally: &Tally,
+			median_outlay: Option<T::Funds>,
+		) -> Result<Vec<(Department, DepartmentFunding<T::Funds>)>, Error<T>> {
+			let ElectionParameters { citizen_share, aggregator, .. } = parameters;
+			let outcome = Self::funds_of(tally, aggregator)?
+				.into_iter()
+				.map(|(department, funds)| Ok((department, Self::checked_convert(funds)?)))
+				.collect::<Result<Vec<(Department, u128)>, Error<T>>>()?;
+			let allocation = LegislativeAllocation::<T>::iter()
+				.map(|(department, weight)| Ok((department, Self::checked_convert(weight)?)))
+				.collect::<Result<Vec<(Department, u128)>, Error<T>>>()?;
+			let votes = Self::checked_sum(outcome.iter().map(|(_, funds)| *funds))?;
+			let weights = Self::checked_sum(allocation.iter().map(|(_, weight)| *weight))?;
+			let outlay: u128 = match median_outlay {
+				Some(outlay) => Self::checked_convert(outlay)?,
+				None if votes == 0 => weights,
+				None => votes,
+			};
+			// Both shares are taken from the same outlay
+			let citizen_outlay = citizen_share * outlay;
+			let legislative_outlay = outlay.saturating_sub(citizen_outlay);
+			// Shares `outlay` proportionally to `part` out of `whole`
+			let share = |outlay: u128, part: u128, whole: u128| match whole {
+				0 => Ok(0),
+				_ => multiply_by_rational_with_rounding(outlay, part, whole, Rounding::Down)
+					.ok_or(Error::<T>::ArithmeticOverflow),
+			};
+
+			let mut split = Vec::new();
+			for (department, funds) in outcome {
+				let weight = allocation
+					.iter()
+					.find(|(allocated, _)| *allocated == department)
+					.map_or(0, |(_, weight)| *weight);
+				let citizen = share(citizen_outlay, funds, votes)?;
+				let legislative = share(legislative_outlay, weight, weights)?;
+				split.push((department, citizen, legislative));
+			}
+			let combined = split
+				.iter()
+				.map(|(department, citizen, legislative)| {
+					Ok((
+						*department,
+						citizen.checked_add(*legislative).ok_or(Error::<T>::ArithmeticOverflow)?,
+					))
+				})
+				.collect::<Result<Vec<(Department, u128)>, Error<T>>>()?;
+
+			split
+				.into_iter()
+				.zip(Self::within_funding_limits(combined)?)
+				.map(|((department, citizen, legislative), (_, funds))| {
+					// Funding a department received only because of its floor is split like the
+					// outlay
+					let citizen = match citizen.saturating_add(legislative) {
+						0 => share(funds, citizen_outlay, outlay)?,
+						whole => share(funds, citizen, whole)?,
+					};
+					let funding = DepartmentFunding {
+						citizen: Self::checked_convert(citizen)?,
+						legislative: Self::checked_convert(funds.saturating_sub(citizen))?,
+					};
+					Ok((department, funding))
+				})
+				.collect()
+		}
+
+		pub fn get_current_block_number() -> BlockNumberFor<T> {
+			frame_system::Pallet::<T>::block_number()
+		}
+
+		/// The current time, in milliseconds since the Unix epoch.
+		pub fn now() -> u64 {
+			T::UnixTime::now().as_millis().saturated_into()
 		}
 
+		/// Whether voting in the current budget election has ended. Elections following a fiscal
+		/// calendar end at their closing time, and the others at their deadline block.
 		pub fn past_deadline() -> bool {
 			match CurrentBudgetElection::<T>::get() {
-				Some(budget_info) => Self::get_current_block_number() >= budget_info.deadline,
+				Some(budget_info) => match budget_info.parameters.calendar {
+					Some(calendar) => Self::now() >= calendar.closes_at,
+					None => Self::get_current_block_number() >= budget_info.deadline,
+				},
 				_ => false,
 			}
 		}
 
+		/// Whether voting in the current budget election has started. Only elections following a
+		/// fiscal calendar can open for voting after they are created.
+		pub fn voting_started() -> bool {
+			match Self::budget_info().and_then(|budget_info| budget_info.parameters.calendar) {
+				Some(calendar) => Self::now() >= calendar.opens_at,
+				None => true,
+			}
+		}
+
+		/// The share of the voting period of the current budget election that is still ahead.
+		pub fn time_left() -> Perbill {
+			let budget_info = match Self::budget_info() {
+				Some(budget_info) => budget_info,
+				None => return Perbill::zero(),
+			};
+			match budget_info.parameters.calendar {
+				Some(FiscalCalendar { opens_at, closes_at, .. }) => {
+					let remaining = closes_at.saturating_sub(Self::now().max(opens_at));
+					Perbill::from_rational(remaining, closes_at.saturating_sub(opens_at))
+				},
+				None => {
+					let remaining: u32 = budget_info
+						.deadline
+						.saturating_sub(Self::get_current_block_number())
+						.saturated_into();
+					Perbill::from_rational(remaining, Self::parameters().budget_lifetime)
+				},
+			}
+		}
+
+		/// Checks citizens can currently vote in the budget election, and returns it.
+		fn ensure_voting_open() -> Result<BudgetInfo<T>, DispatchError> {
+			// Check that the budget is marked as open
+			let budget_info = match CurrentBudgetElection::<T>::get() {
+				Some(budget_info) if budget_info.is_open => budget_info,
+				_ => fail!(Error::<T>::BudgetIsClosed),
+			};
+
+			ensure!(Self::voting_started(), Error::<T>::VotingNotStarted);
+			// Every citizen needs to have received their credit
+			ensure!(!PendingCreditUpdate::<T>::exists(), Error::<T>::CreditUpdateInProgress);
+
+			// Check that the citizen is not trying to vote after the deadline (someone will need
+			// to manually close the budget
+			if Self::past_deadline() {
+				fail!(Error::<T>::CannotVotePastDeadline)
+			}
+
+			Ok(budget_info)
+		}
+
+		/// The governance parameters currently in force.
+		pub fn parameters() -> GovernanceParameters<T::VotingCredit> {
+			Parameters::<T>::get()
+		}
+
+		/// Checks a citizen is registered, holds a credential and is not suspended, and returns
+		/// their record.
+		fn ensure_eligible(citizen: &T::AccountId) -> Result<CitizenInfo<T>, DispatchError> {
+			let citizen_info =
+				RegisteredCitizens::<T>::get(citizen).ok_or(Error::<T>::CitizenNotRegistered)?;
+			ensure!(T::Credential::holds(citizen), Error::<T>::NoCitizenshipCredential);
+			ensure!(!citizen_info.suspended, Error::<T>::CitizenSuspended);
+			// Citizens who registered themselves are only eligible while their identity is verified
+			ensure!(
+				!ClaimedCitizens::<T>::contains_key(citizen) ||
+					T::IdentityVerifier::is_verified(citizen),
+				Error::<T>::IdentityNotVerified
+			);
+			Ok(citizen_info)
+		}
+
 		/// Returns the information regarding a proposal
 		pub fn budget_info() -> Option<BudgetInfo<T>> {
 			CurrentBudgetElection::<T>::get()
 		}
 
+		/// Converts between the numeric types of this pallet and the balances it mints, failing
+		/// if the value does not fit.
+		fn checked_convert<A: TryInto<u128>, B: TryFrom<u128>>(value: A) -> Result<B, Error<T>> {
+			value
+				.try_into()
+				.ok()
+				.and_then(|value| B::try_from(value).ok())
+				.ok_or(Error::<T>::ArithmeticOverflow)
+		}
+
+		fn checked_sum(values: impl IntoIterator<Item = u128>) -> Result<u128, Error<T>> {
+			values
+				.into_iter()
+				.try_fold(0u128, |total, value| total.checked_add(value))
+				.ok_or(Error::<T>::ArithmeticOverflow)
+		}
+
 		pub fn mint_funds(account_id: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
 			match T::NativeBalance::mint_into(account_id, amount) {
 				Ok(_) => Ok(()),
@@ -351,25 +2532,353 @@ pub mod pallet {
 			}
 		}
 
+		pub fn mint_asset_funds(
+			asset: AssetIdOf<T>,
+			account_id: &T::AccountId,
+			amount: AssetBalanceOf<T>,
+		) -> DispatchResult {
+			T::Fungibles::mint_into(asset, account_id, amount).map(|_| ())
+		}
+
+		/// Mints voting credit into a citizen's credit asset.
+		fn mint_credit(
+			credit_asset: AssetIdOf<T>,
+			citizen: &T::AccountId,
+			credit: T::VotingCredit,
+		) -> DispatchResult {
+			if credit.is_zero() {
+				return Ok(())
+			}
+			T::Fungibles::mint_into(credit_asset, citizen, Self::checked_convert(credit)?)
+				.map(|_| ())
+		}
+
+		/// Burns credit from a citizen's credit asset, lifting the freeze while it does so.
+		fn burn_credit(
+			credit_asset: AssetIdOf<T>,
+			citizen: &T::AccountId,
+			amount: AssetBalanceOf<T>,
+		) -> DispatchResult {
+			CreditThawed::<T>::put(true);
+			let burnt = T::Fungibles::burn_from(
+				credit_asset,
+				citizen,
+				amount,
+				Precision::Exact,
+				Fortitude::Force,
+			);
+			CreditThawed::<T>::kill();
+			burnt.map(|_| ())
+		}
+
+		/// Burns whatever is left of a citizen's credit asset for the current budget election.
+		fn burn_remaining_credit(citizen: &T::AccountId) -> DispatchResult {
+			match Self::budget_info().and_then(|budget_info| budget_info.credit_asset) {
+				Some(credit_asset) => {
+					let remaining = T::Fungibles::balance(credit_asset.clone(), citizen);
+					if remaining.is_zero() {
+						return Ok(())
+					}
+					Self::burn_credit(credit_asset, citizen, remaining)
+				},
+				None => Ok(()),
+			}
+		}
+
+		/// The account holding the taxes paid by citizens.
+		pub fn treasury_account_id() -> T::AccountId {
+			T::PalletId::get().into_sub_account_truncating(b"treasury")
+		}
+
+		/// The taxes currently held by the budget treasury.
+		pub fn treasury_balance() -> BalanceOf<T> {
+			T::NativeBalance::balance(&Self::treasury_account_id())
+		}
+
+		/// The fiscal period taxes are currently paid into, i.e. the next budget election's id.
+		pub fn current_fiscal_period() -> T::BudgetId {
+			match CurrentBudgetElection::<T>::get() {
+				Some(budget_info) => budget_info.budget_id.saturating_add(One::one()),
+				None => Zero::zero(),
+			}
+		}
+
+		/// What all citizens together paid in taxes during a fiscal period.
+		pub fn total_contributions(period: T::BudgetId) -> BalanceOf<T> {
+			TotalTaxContributions::<T>::get(period)
+		}
+
+		/// The account administering the assets created by this pallet.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account_truncating()
+		}
+
 		pub fn get_department_acc(department: Department) -> T::AccountId {
 			let bytes = T::Hashing::hash(&department.encode());
 			T::AccountId::decode(&mut TrailingZeroInput::new(&bytes.encode()))
 				.expect("we assume all bytes can be turned into some account id")
 		}
 
-		pub fn get_citizen_voting_credit(citizen: &T::AccountId) -> Option<VotingCredit> {
+		pub fn get_citizen_voting_credit(citizen: &T::AccountId) -> Option<T::VotingCredit> {
+			RegisteredCitizens::<T>::get(citizen).map(|citizen_info| citizen_info.credit)
+		}
+
+		/// The record of a registered citizen.
+		pub fn citizen_info(citizen: &T::AccountId) -> Option<CitizenInfo<T>> {
 			RegisteredCitizens::<T>::get(citizen)
 		}
 
+		/// The applications to register as a citizen the registrar has not decided on yet. Goes
+		/// through every application, so it is meant for queries rather than extrinsics.
+		pub fn pending_applications() -> Vec<(T::AccountId, RegistrationApplication<T>)> {
+			RegistrationApplications::<T>::iter().collect()
+		}
+
+		/// The citizens registered in a region. Goes through every citizen, so it is meant for
+		/// queries rather than extrinsics.
+		pub fn citizens_in_region(region: u32) -> Vec<T::AccountId> {
+			RegisteredCitizens::<T>::iter()
+				.filter(|(_, citizen_info)| citizen_info.region == Some(region))
+				.map(|(citizen, _)| citizen)
+				.collect()
+		}
+
+		/// How many citizens are registered.
+		pub fn citizen_count() -> u32 {
+			CitizenCount::<T>::get()
+		}
+
+		/// How many citizens have voted in the current budget election.
+		pub fn voter_count() -> u32 {
+			VoterCount::<T>::get()
+		}
+
+		/// The credit a citizen carried over into a budget election.
+		pub fn carried_over_credit(
+			budget_id: T::BudgetId,
+			citizen: &T::AccountId,
+		) -> T::VotingCredit {
+			CarriedOverCredit::<T>::get(budget_id, citizen)
+		}
+
 		pub fn balance_of(
 			department: Department,
 		) -> <T::NativeBalance as Inspect<T::AccountId>>::Balance {
 			T::NativeBalance::balance(&Self::get_department_acc(department))
 		}
+
+		/// The balance of a department in the given budget asset.
+		pub fn asset_balance_of(asset: AssetIdOf<T>, department: Department) -> AssetBalanceOf<T> {
+			T::Fungibles::balance(asset, &Self::get_department_acc(department))
+		}
+	}
+
+	/// The taxes recorded through `pay_tax`.
+	impl<T: Config> ContributionProvider<T::AccountId, T::BudgetId, BalanceOf<T>> for Pallet<T> {
+		fn contribution_of(who: &T::AccountId, budget_id: T::BudgetId) -> BalanceOf<T> {
+			TaxContributions::<T>::get(budget_id, who)
+		}
+	}
+
+	/// Freezes the whole balance of the current credit asset, so voting credit can only be spent
+	/// by voting and never transferred.
+	impl<T: Config> pallet_assets::FrozenBalance<AssetIdOf<T>, T::AccountId, AssetBalanceOf<T>>
+		for Pallet<T>
+	{
+		fn frozen_balance(asset: AssetIdOf<T>, who: &T::AccountId) -> Option<AssetBalanceOf<T>> {
+			if CreditThawed::<T>::get() {
+				return None
+			}
+			Self::budget_info()
+				.and_then(|budget_info| budget_info.credit_asset)
+				.filter(|credit_asset| *credit_asset == asset)
+				.map(|credit_asset| T::Fungibles::balance(credit_asset, who))
+		}
+
+		fn died(_asset: AssetIdOf<T>, _who: &T::AccountId) {}
+	}
+
+	/// The parameters a budget election is opened with.
+	#[derive(
+		Clone,
+		Copy,
+		PartialEq,
+		Eq,
+		RuntimeDebug,
+		Encode,
+		Decode,
+		TypeInfo,
+		MaxEncodedLen,
+		frame_support::Serialize,
+		frame_support::Deserialize,
+	)]
+	#[serde(crate = "frame_support::serde")]
+	pub struct ElectionParameters<Funds> {
+		/// The share of the budget citizens vote on. The legislature allocates the rest.
+		pub citizen_share: Perbill,
+		/// If set, citizens are also asked for the total outlay of the budget, within this range.
+		pub outlay_range: Option<OutlayRange<Funds>>,
+		/// How the citizens' ballots are aggregated into the funding of each department.
+		pub aggregator: Aggregator,
+		/// Whether citizens can spend exact amounts of credit for fractional funding.
+		pub funding_units: FundingUnits,
+		/// If set, voting follows the fiscal calendar rather than the deadline block.
+		pub calendar: Option<FiscalCalendar>,
+	}
+
+	impl<Funds> Default for ElectionParameters<Funds> {
+		fn default() -> Self {
+			Self {
+				citizen_share: Perbill::one(),
+				outlay_range: None,
+				aggregator: Aggregator::default(),
+				funding_units: FundingUnits::default(),
+				calendar: None,
+			}
+		}
+	}
+
+	/// Parameters governance can change without a runtime upgrade.
+	#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	pub struct GovernanceParameters<VotingCredit> {
+		/// The voting credit given to every citizen by `FlatCredit`.
+		pub given_voting_credit: VotingCredit,
+		/// How many blocks budget elections not following a fiscal calendar last for.
+		pub budget_lifetime: u32,
+		/// The most citizens that can be registered.
+		pub max_registered_citizens: u32,
+		/// The most budget elections that can be held.
+		pub max_budget_elections: u32,
+	}
+
+	/// How citizens express the funding they give to departments.
+	#[derive(
+		Clone,
+		Copy,
+		Default,
+		PartialEq,
+		Eq,
+		RuntimeDebug,
+		Encode,
+		Decode,
+		TypeInfo,
+		MaxEncodedLen,
+		frame_support::Serialize,
+		frame_support::Deserialize,
+	)]
+	#[serde(crate = "frame_support::serde")]
+	pub enum FundingUnits {
+		/// Citizens fund whole units, paying their square in credit.
+		#[default]
+		Whole,
+		/// Citizens can also spend an exact amount of credit, and fund its square root.
+		Fractional,
+	}
+
+	/// How budget elections are opened automatically.
+	#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	pub struct ElectionRecurrence<BlockNumber, Funds> {
+		/// The number of blocks between the opening of two elections.
+		pub period: BlockNumber,
+		/// How many blocks into each period elections open.
+		pub offset: BlockNumber,
+		/// The parameters every election is opened with. They cannot follow a fiscal calendar.
+		pub parameters: ElectionParameters<Funds>,
+	}
+
+	/// How much longer voting in a budget election lasts when extended.
+	#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	pub enum ElectionExtension<BlockNumber> {
+		/// Moves the deadline block of an election not following a fiscal calendar.
+		Blocks(BlockNumber),
+		/// Moves the closing time of an election following a fiscal calendar.
+		Millis(u64),
+	}
+
+	/// When voting in a budget election takes place, following calendar dates rather than blocks.
+	/// Times are in milliseconds since the Unix epoch.
+	#[derive(
+		Clone,
+		Copy,
+		PartialEq,
+		Eq,
+		RuntimeDebug,
+		Encode,
+		Decode,
+		TypeInfo,
+		MaxEncodedLen,
+		frame_support::Serialize,
+		frame_support::Deserialize,
+	)]
+	#[serde(crate = "frame_support::serde")]
+	pub struct FiscalCalendar {
+		/// The fiscal year the budget is for, e.g. 2024.
+		pub fiscal_year: u32,
+		/// Votes are accepted from this time on.
+		pub opens_at: u64,
+		/// Votes are no longer accepted from this time on, and the budget can be closed.
+		pub closes_at: u64,
+	}
+
+	/// The range of total outlays citizens can propose, both ends included.
+	#[derive(
+		Clone,
+		Copy,
+		PartialEq,
+		Eq,
+		RuntimeDebug,
+		Encode,
+		Decode,
+		TypeInfo,
+		MaxEncodedLen,
+		frame_support::Serialize,
+		frame_support::Deserialize,
+	)]
+	#[serde(crate = "frame_support::serde")]
+	pub struct OutlayRange<Funds> {
+		pub min: Funds,
+		pub max: Funds,
+	}
+
+	/// The funds a department received from a budget election.
+	#[derive(
+		Clone, Copy, Default, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen,
+	)]
+	pub struct DepartmentFunding<Funds> {
+		/// Funds from the portion voted by citizens.
+		pub citizen: Funds,
+		/// Funds from the portion allocated by the legislature.
+		pub legislative: Funds,
+	}
+
+	/// The least and most a department can receive, as a share of the total outlay.
+	#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	pub struct FundingLimit {
+		pub floor: Perbill,
+		pub cap: Perbill,
+	}
+
+	impl Default for FundingLimit {
+		fn default() -> Self {
+			Self { floor: Perbill::zero(), cap: Perbill::one() }
+		}
 	}
 
 	/// The set of choices for a citizen vote for.
-	#[derive(Debug, Copy, Clone, EnumIter, PartialEq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	#[derive(
+		Debug,
+		Copy,
+		Clone,
+		EnumIter,
+		PartialEq,
+		Encode,
+		Decode,
+		TypeInfo,
+		MaxEncodedLen,
+		frame_support::Serialize,
+		frame_support::Deserialize,
+	)]
+	#[serde(crate = "frame_support::serde")]
 	#[scale_info(skip_type_params(T))]
 	pub enum Department {
 		Education,
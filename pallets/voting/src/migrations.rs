@@ -0,0 +1,152 @@
+//! Migrations of the pallet's storage between versions.
+use crate::{
+	BudgetDistribution, BudgetInfo, CitizenCount, CitizenInfo, Config, CurrentBudgetElection,
+	Pallet, RegisteredCitizens, VoterCount,
+};
+use frame_support::{
+	pallet_prelude::*,
+	sp_runtime::traits::{Saturating, Zero},
+	traits::OnRuntimeUpgrade,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use scale_info::prelude::collections::BTreeSet;
+#[cfg(feature = "try-runtime")]
+use scale_info::prelude::vec::Vec;
+
+/// The storage layout before version 1, where citizens only had their credit recorded, and
+/// budget ids, credit and funds were all `u32`.
+pub mod v0 {
+	use super::*;
+
+	#[frame_support::storage_alias]
+	pub type RegisteredCitizens<T: Config> =
+		StorageMap<Pallet<T>, Blake2_128Concat, <T as frame_system::Config>::AccountId, u32>;
+
+	/// A budget election, before its assets and parameters were recorded.
+	#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+	pub struct BudgetInfo<BlockNumber> {
+		pub budget_id: u32,
+		pub deadline: BlockNumber,
+		pub is_open: bool,
+	}
+
+	#[frame_support::storage_alias]
+	pub type CurrentBudgetElection<T: Config> =
+		StorageValue<Pallet<T>, BudgetInfo<BlockNumberFor<T>>>;
+
+	#[frame_support::storage_alias]
+	pub type BudgetDistribution<T: Config> = StorageDoubleMap<
+		Pallet<T>,
+		Blake2_128Concat,
+		crate::Department,
+		Blake2_128Concat,
+		<T as frame_system::Config>::AccountId,
+		u32,
+	>;
+}
+
+/// Turns the credit recorded for each citizen into a `CitizenInfo`, and counts citizens. The
+/// current budget election keeps its id, so the next one does not reuse the ids its results
+/// are recorded under, and the votes cast in it are kept while it is open, along with who cast
+/// them so they can be counted when it closes.
+pub mod v1 {
+	use super::*;
+
+	pub struct MigrateToV1<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() != 0 {
+				return T::DbWeight::get().reads(1)
+			}
+
+			// When citizens were registered and by whom was not recorded
+			let mut count = 0u32;
+			RegisteredCitizens::<T>::translate::<u32, _>(|_, credit| {
+				count.saturating_inc();
+				Some(CitizenInfo {
+					credit: credit.into(),
+					registered_at: Zero::zero(),
+					registered_by: None,
+					region: None,
+					last_voted_budget: None,
+					suspended: false,
+				})
+			});
+			CitizenCount::<T>::put(count);
+
+			// Elections were funded in the native token, with the default parameters
+			let budget_info = CurrentBudgetElection::<T>::translate::<
+				v0::BudgetInfo<BlockNumberFor<T>>,
+				_,
+			>(|old| {
+				old.map(|old| BudgetInfo {
+					budget_id: old.budget_id.into(),
+					deadline: old.deadline,
+					is_open: old.is_open,
+					asset: None,
+					credit_asset: None,
+					parameters: Default::default(),
+				})
+			})
+			.ok()
+			.flatten();
+
+			// Votes are now cleared when an election closes, rather than when the next one opens
+			let mut votes = 0u32;
+			match budget_info {
+				Some(budget_info) if budget_info.is_open => {
+					let mut voters = BTreeSet::new();
+					BudgetDistribution::<T>::translate::<u32, _>(|_, citizen, funds| {
+						votes.saturating_inc();
+						// Votes of citizens deregistered since are withdrawn, as they are now
+						if !RegisteredCitizens::<T>::contains_key(&citizen) {
+							return None
+						}
+						voters.insert(citizen);
+						Some(funds.into())
+					});
+					for citizen in voters.iter() {
+						RegisteredCitizens::<T>::mutate(citizen, |citizen_info| {
+							if let Some(citizen_info) = citizen_info {
+								citizen_info.last_voted_budget = Some(budget_info.budget_id);
+							}
+						});
+					}
+					VoterCount::<T>::put(voters.len() as u32);
+					votes.saturating_accrue(voters.len() as u32);
+				},
+				_ => {
+					votes = v0::BudgetDistribution::<T>::clear(u32::MAX, None).unique;
+				},
+			}
+			StorageVersion::new(1).put::<Pallet<T>>();
+
+			let accessed = count as u64 + votes as u64;
+			T::DbWeight::get().reads_writes(accessed + 2, accessed + 4)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, frame_support::sp_runtime::TryRuntimeError> {
+			let count = v0::RegisteredCitizens::<T>::iter_keys().count() as u32;
+			let budget_id = v0::CurrentBudgetElection::<T>::get().map(|old| old.budget_id);
+			Ok((count, budget_id).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+			let (count, budget_id) = <(u32, Option<u32>)>::decode(&mut &state[..])
+				.map_err(|_| "the pre-upgrade state is invalid")?;
+			ensure!(CitizenCount::<T>::get() == count, "the citizens were not all counted");
+			ensure!(
+				RegisteredCitizens::<T>::iter_values().count() as u32 == count,
+				"the citizens were not all migrated"
+			);
+			ensure!(
+				CurrentBudgetElection::<T>::get().map(|budget_info| budget_info.budget_id) ==
+					budget_id.map(Into::into),
+				"the current budget election was not migrated"
+			);
+			Ok(())
+		}
+	}
+}
@@ -1,10 +1,22 @@
 use crate as pallet_voting;
-use frame_support::traits::{AsEnsureOriginWithArg, ConstU128, ConstU16, ConstU32, ConstU64};
-use frame_system::{EnsureRoot, EnsureSigned};
+use crate::{
+	CitizenshipCredential, ContributionCredit, ContributionCurve, ContributionWeighting,
+	CreditAllocator, FlatCredit, IdentityVerifier, ProRataLateRegistrants,
+};
+use core::time::Duration;
+use frame_support::{
+	dispatch::DispatchResult,
+	ord_parameter_types, parameter_types,
+	traits::{
+		AsEnsureOriginWithArg, ConstU128, ConstU16, ConstU32, ConstU64, EitherOfDiverse, UnixTime,
+	},
+	PalletId,
+};
+use frame_system::{EnsureRoot, EnsureSigned, EnsureSignedBy};
 use sp_core::H256;
 use sp_runtime::{
-	traits::{BlakeTwo256, IdentityLookup},
-	BuildStorage,
+	traits::{BlakeTwo256, Convert, IdentityLookup},
+	BuildStorage, Perbill,
 };
 
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -55,7 +67,7 @@ impl pallet_balances::Config for Test {
 	type ExistentialDeposit = ConstU128<1>;
 	type AccountStore = System;
 	type ReserveIdentifier = [u8; 8];
-	type RuntimeHoldReason = ();
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type FreezeIdentifier = ();
 	type MaxLocks = ConstU32<10>;
 	type MaxReserves = ();
@@ -63,16 +75,148 @@ impl pallet_balances::Config for Test {
 	type MaxFreezes = ConstU32<10>;
 }
 
+parameter_types! {
+	pub const VotingPalletId: PalletId = PalletId(*b"py/votng");
+	/// The first asset id budgets are issued in, if any. Each election gets its own asset.
+	pub static BudgetAssetBase: Option<u32> = None;
+	/// The first asset id voting credit is mirrored in, if any. Each election gets its own asset.
+	pub static CreditAssetBase: Option<u32> = None;
+	pub static CarryoverFraction: Perbill = Perbill::zero();
+	/// The current time, in milliseconds since the Unix epoch.
+	pub static Now: u64 = 0;
+	pub static CreditRule: MockCreditRule = MockCreditRule::Flat;
+	pub static Weighting: ContributionWeighting<u32> = ContributionWeighting {
+		floor: 0,
+		cap: u32::MAX,
+		curve: ContributionCurve::Linear,
+		contribution_unit: 1,
+	};
+	/// The accounts whose identity a trusted registrar has judged positively.
+	pub static VerifiedIdentities: Vec<u64> = Vec::new();
+	/// The citizenship credentials issued, with their serial numbers.
+	pub static Credentials: Vec<(u64, u32)> = Vec::new();
+	/// The most citizens whose credit is updated in a block.
+	pub static CitizensPerBlock: u32 = 100;
+	/// The most voters whose ballots are counted in a block.
+	pub static VotersPerBlock: u32 = 100;
+}
+
+ord_parameter_types! {
+	pub const CivilRegistry: u64 = 100;
+	pub const ElectoralCommission: u64 = 200;
+	pub const Treasury: u64 = 300;
+}
+
+/// A clock tests can move freely.
+pub struct MockTime;
+impl UnixTime for MockTime {
+	fn now() -> Duration {
+		Duration::from_millis(Now::get())
+	}
+}
+
+#[derive(Clone, Copy)]
+pub enum MockCreditRule {
+	Flat,
+	ContributionWeighted,
+	ProRataLateRegistrants,
+}
+
+type ProRata = ProRataLateRegistrants<FlatCredit>;
+
+pub struct MockCreditAllocator;
+impl CreditAllocator<Test> for MockCreditAllocator {
+	fn allocate(who: &u64, budget_id: u32) -> u32 {
+		match CreditRule::get() {
+			MockCreditRule::Flat => <FlatCredit as CreditAllocator<Test>>::allocate(who, budget_id),
+			MockCreditRule::ContributionWeighted =>
+				<ContributionCredit<Weighting> as CreditAllocator<Test>>::allocate(who, budget_id),
+			MockCreditRule::ProRataLateRegistrants =>
+				<ProRata as CreditAllocator<Test>>::allocate(who, budget_id),
+		}
+	}
+
+	fn allocate_late(who: &u64, budget_id: u32) -> u32 {
+		match CreditRule::get() {
+			MockCreditRule::ProRataLateRegistrants =>
+				<ProRata as CreditAllocator<Test>>::allocate_late(who, budget_id),
+			_ => 0,
+		}
+	}
+}
+
+pub struct MockIdentityVerifier;
+impl IdentityVerifier<u64> for MockIdentityVerifier {
+	fn is_verified(who: &u64) -> bool {
+		VerifiedIdentities::get().contains(who)
+	}
+}
+
+pub struct MockCredential;
+impl CitizenshipCredential<u64> for MockCredential {
+	fn issue(who: &u64, serial: u32) -> DispatchResult {
+		Credentials::mutate(|credentials| credentials.push((*who, serial)));
+		Ok(())
+	}
+
+	fn revoke(who: &u64) -> DispatchResult {
+		Credentials::mutate(|credentials| credentials.retain(|(holder, _)| holder != who));
+		Ok(())
+	}
+
+	fn holds(who: &u64) -> bool {
+		Credentials::get().iter().any(|(holder, _)| holder == who)
+	}
+}
+
+pub struct BudgetAssetPerElection;
+impl Convert<u32, Option<u32>> for BudgetAssetPerElection {
+	fn convert(budget_id: u32) -> Option<u32> {
+		BudgetAssetBase::get().map(|base| base + budget_id)
+	}
+}
+
+pub struct CreditAssetPerElection;
+impl Convert<u32, Option<u32>> for CreditAssetPerElection {
+	fn convert(budget_id: u32) -> Option<u32> {
+		CreditAssetBase::get().map(|base| base + budget_id)
+	}
+}
+
 impl pallet_voting::Config for Test {
 	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type RuntimeEvent = RuntimeEvent;
+	type BudgetId = u32;
+	type VotingCredit = u32;
+	type Funds = u32;
 	type NativeBalance = Balances;
 	type Fungibles = Assets;
+	type PalletId = VotingPalletId;
+	type RegistrarOrigin = EitherOfDiverse<EnsureRoot<u64>, EnsureSignedBy<CivilRegistry, u64>>;
+	type ElectionAdminOrigin =
+		EitherOfDiverse<EnsureRoot<u64>, EnsureSignedBy<ElectoralCommission, u64>>;
+	type TreasuryOrigin = EitherOfDiverse<EnsureRoot<u64>, EnsureSignedBy<Treasury, u64>>;
+	type BudgetAsset = BudgetAssetPerElection;
+	type CreditAsset = CreditAssetPerElection;
 	type RuntimeCall = RuntimeCall;
 	type GivenVotingCredit = ConstU32<4096>;
+	type CreditAllocator = MockCreditAllocator;
+	type Contributions = Voting;
 	type MaxRegisteredCitizens = ConstU32<1_000_000>;
 	type MaxBudgetElections = ConstU32<1_000>;
 	type BudgetLifetime = ConstU32<1_000>;
+	type UnixTime = MockTime;
+	type CarryoverFraction = CarryoverFraction;
+	type MaxCarryover = ConstU32<1_024>;
+	type MinNetFunds = ConstU32<0>;
+	type IdentityVerifier = MockIdentityVerifier;
+	type Credential = MockCredential;
+	type RegistrationDeposit = ConstU128<10>;
+	type MaxCitizensPerCall = ConstU32<100>;
+	type CitizensPerBlock = CitizensPerBlock;
+	type VotersPerBlock = VotersPerBlock;
+	type WeightInfo = ();
 }
 
 impl pallet_assets::Config for Test {
@@ -89,7 +233,7 @@ impl pallet_assets::Config for Test {
 	type MetadataDepositPerByte = ConstU128<1>;
 	type ApprovalDeposit = ConstU128<1>;
 	type StringLimit = ConstU32<50>;
-	type Freezer = ();
+	type Freezer = Voting;
 	type Extra = ();
 	type CallbackHandle = ();
 	type WeightInfo = ();
@@ -98,7 +242,16 @@ impl pallet_assets::Config for Test {
 	type BenchmarkHelper = ();
 }
 
+/// The taxes the treasury holds at genesis, enough to pay for every budget in the tests.
+pub const TREASURY_POT: Balance = 1_000_000;
+
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	frame_system::GenesisConfig::<Test>::default().build_storage().unwrap().into()
+	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(Voting::treasury_account_id(), TREASURY_POT)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	t.into()
 }
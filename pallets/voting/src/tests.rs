@@ -1,9 +1,18 @@
 #[cfg(test)]
 mod tests {
 	use crate::{
-		mock, mock::*, BudgetDistribution, BudgetInfo, Department, Error, Event, RegisteredCitizens,
+		migrations, mock, mock::*, Aggregator, BudgetDistribution, BudgetHistory, BudgetInfo,
+		CarriedOverCredit, CertifiedBudgets, ContributionCurve, ContributionWeighting, Department,
+		DepartmentFunding, ElectionExtension, ElectionParameters, ElectionRecurrence, Error, Event,
+		FiscalCalendar, FundingLimits, FundingUnits, GovernanceParameters, OutlayRange,
+		TaxContributions,
 	};
-	use frame_support::{assert_noop, assert_ok};
+	use frame_support::{
+		assert_noop, assert_ok,
+		traits::{fungibles::roles::Inspect as Roles, Hooks, OnRuntimeUpgrade, StorageVersion},
+	};
+	use sp_core::H256;
+	use sp_runtime::{BuildStorage, DispatchError, FixedU128, Perbill, TokenError};
 
 	#[test]
 	fn citizen_registration_and_voting() {
@@ -17,7 +26,7 @@ mod tests {
 
 			// Open a budget election
 			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
-			System::assert_last_event(Event::BudgetOpen.into());
+			System::assert_has_event(Event::BudgetOpen.into());
 
 			// Citizen 1 should now be able to vote
 			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Military, 10));
@@ -58,7 +67,7 @@ mod tests {
 			System::assert_last_event(Event::CitizenRegistered { who: 2 }.into());
 
 			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
-			System::assert_last_event(Event::BudgetOpen.into());
+			System::assert_has_event(Event::BudgetOpen.into());
 
 			// Register citizen 3 after is budget is open (will have no funds)
 			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 3));
@@ -88,11 +97,11 @@ mod tests {
 
 			// Register citizen 1
 			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
-			assert_eq!(RegisteredCitizens::<Test>::get(1), Some(0));
+			assert_eq!(Voting::get_citizen_voting_credit(&1), Some(0));
 			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
 
 			// After budget opening, their voting credit should be 4096
-			assert_eq!(RegisteredCitizens::<Test>::get(1), Some(4096));
+			assert_eq!(Voting::get_citizen_voting_credit(&1), Some(4096));
 
 			// Fund the military with 20 tokens
 			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Military, 20));
@@ -102,7 +111,7 @@ mod tests {
 			);
 
 			// Citizen 1's voting credit should be 4096 - 20^2 = 3696
-			assert_eq!(RegisteredCitizens::<Test>::get(1), Some(3696));
+			assert_eq!(Voting::get_citizen_voting_credit(&1), Some(3696));
 
 			// Fund Education with 30 tokens (2796 left)
 			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 30));
@@ -143,11 +152,11 @@ mod tests {
 			System::set_block_number(1);
 
 			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
-			assert_eq!(RegisteredCitizens::<Test>::get(1), Some(0));
+			assert_eq!(Voting::get_citizen_voting_credit(&1), Some(0));
 
 			// Root successfully opens budget
 			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
-			System::assert_last_event(Event::BudgetOpen.into());
+			System::assert_has_event(Event::BudgetOpen.into());
 
 			// Root should fail to open the budget again
 			assert_noop!(
@@ -156,7 +165,7 @@ mod tests {
 			);
 
 			// Citizen 1 balance is now 4096
-			assert_eq!(RegisteredCitizens::<Test>::get(1), Some(4096));
+			assert_eq!(Voting::get_citizen_voting_credit(&1), Some(4096));
 
 			// Voting
 			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 30));
@@ -189,7 +198,7 @@ mod tests {
 			);
 
 			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
-			System::assert_last_event(Event::BudgetClosed.into());
+			System::assert_has_event(Event::BudgetClosed.into());
 
 			// Attempt to close again
 			assert_noop!(
@@ -202,4 +211,1417 @@ mod tests {
 			assert_eq!(Voting::balance_of(Department::Healthcare), 50);
 		})
 	}
+
+	#[test]
+	fn budget_issued_in_dedicated_asset() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			BudgetAssetBase::set(Some(100));
+
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+
+			// The asset for the first budget election is created and administered by the pallet
+			System::assert_has_event(Event::BudgetAssetCreated { asset: 100 }.into());
+			assert_eq!(Voting::budget_info().unwrap().asset, Some(100));
+			assert_eq!(<Assets as Roles<u64>>::owner(100), Some(Voting::account_id()));
+
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 30));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Healthcare, 50));
+
+			System::set_block_number(1001);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+
+			// Allocations are issued in the budget asset, not in the native token
+			assert_eq!(Voting::asset_balance_of(100, Department::Education), 30);
+			assert_eq!(Voting::asset_balance_of(100, Department::Healthcare), 50);
+			assert_eq!(Voting::balance_of(Department::Education), 0);
+		})
+	}
+
+	#[test]
+	fn voting_credit_mirrored_in_frozen_asset() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			CreditAssetBase::set(Some(200));
+
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 2));
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+
+			// Credit is minted to every citizen when the budget opens
+			assert_eq!(Assets::balance(200, 1), 4096);
+			assert_eq!(Assets::balance(200, 2), 4096);
+
+			// Credit cannot be transferred to someone else
+			assert!(Assets::transfer(RuntimeOrigin::signed(1), 200.into(), 2, 100).is_err());
+			assert_eq!(Assets::balance(200, 2), 4096);
+
+			// Credit is burned when voting
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Military, 20));
+			assert_eq!(Assets::balance(200, 1), 3696);
+			assert_eq!(Voting::get_citizen_voting_credit(&1), Some(3696));
+
+			// Unspent credit is burned when the budget closes
+			System::set_block_number(1001);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+			assert_eq!(Assets::balance(200, 1), 0);
+			assert_eq!(Assets::balance(200, 2), 0);
+		})
+	}
+
+	#[test]
+	fn contribution_weighted_credit() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			CreditRule::set(MockCreditRule::ContributionWeighted);
+			Weighting::set(ContributionWeighting {
+				floor: 100,
+				cap: 10_000,
+				curve: ContributionCurve::SquareRoot,
+				contribution_unit: 10,
+			});
+			TaxContributions::<Test>::insert(0, 1, 10_000_000);
+			TaxContributions::<Test>::insert(0, 3, 100_000_000_000);
+			TaxContributions::<Test>::insert(1, 1, 500);
+
+			for citizen in 1..=3 {
+				assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), citizen));
+			}
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+
+			// sqrt(10_000_000 / 10) = 1000
+			assert_eq!(Voting::get_citizen_voting_credit(&1), Some(1000));
+			// Citizens who contributed nothing still get the floor
+			assert_eq!(Voting::get_citizen_voting_credit(&2), Some(100));
+			// sqrt(100_000_000_000 / 10) = 100_000, capped
+			assert_eq!(Voting::get_citizen_voting_credit(&3), Some(10_000));
+		})
+	}
+
+	#[test]
+	fn paying_taxes() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), 1, 1_000));
+
+			// Only citizens can pay taxes
+			assert_noop!(
+				Voting::pay_tax(RuntimeOrigin::signed(1), 300),
+				Error::<Test>::CitizenNotRegistered
+			);
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+
+			// Taxes paid before the first election count towards fiscal period 0
+			assert_ok!(Voting::pay_tax(RuntimeOrigin::signed(1), 300));
+			System::assert_last_event(
+				Event::TaxPaid { who: 1, period: 0, amount: 300, total: 300 }.into(),
+			);
+			assert_ok!(Voting::pay_tax(RuntimeOrigin::signed(1), 200));
+			System::assert_last_event(
+				Event::TaxPaid { who: 1, period: 0, amount: 200, total: 500 }.into(),
+			);
+			assert_eq!(Balances::free_balance(1), 500);
+			assert_eq!(Voting::treasury_balance(), TREASURY_POT + 500);
+			assert_eq!(Voting::total_contributions(0), 500);
+
+			// Once the election opens, taxes go towards the next fiscal period
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			assert_ok!(Voting::pay_tax(RuntimeOrigin::signed(1), 100));
+			System::assert_last_event(
+				Event::TaxPaid { who: 1, period: 1, amount: 100, total: 100 }.into(),
+			);
+			assert_eq!(TaxContributions::<Test>::get(0, 1), 500);
+			assert_eq!(Voting::treasury_balance(), TREASURY_POT + 600);
+		})
+	}
+
+	#[test]
+	fn pro_rata_credit_for_late_registrants() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			CreditRule::set(MockCreditRule::ProRataLateRegistrants);
+
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			assert_eq!(Voting::get_citizen_voting_credit(&1), Some(4096));
+
+			// Registered with a quarter of the election left: 4096 / 4 = 1024
+			System::set_block_number(751);
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 2));
+			assert_eq!(Voting::get_citizen_voting_credit(&2), Some(1024));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(2), Department::Education, 32));
+
+			// Nothing is given once the election is past its deadline
+			System::set_block_number(1001);
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 3));
+			assert_eq!(Voting::get_citizen_voting_credit(&3), Some(0));
+		})
+	}
+
+	#[test]
+	fn unspent_credit_carryover() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			CarryoverFraction::set(Perbill::from_percent(50));
+
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 2));
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+
+			// Citizen 1 leaves 3696 credit unspent and citizen 2 leaves 496
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Military, 20));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(2), Department::Education, 60));
+
+			System::set_block_number(1001);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+
+			// Half of it is carried over, up to 1024
+			assert_eq!(Voting::carried_over_credit(1, &1), 1024);
+			assert_eq!(Voting::carried_over_credit(1, &2), 248);
+			System::assert_has_event(
+				Event::CreditCarriedOver { who: 2, from_budget: 0, amount: 248 }.into(),
+			);
+
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			assert_eq!(Voting::get_citizen_voting_credit(&1), Some(4096 + 1024));
+			assert_eq!(Voting::get_citizen_voting_credit(&2), Some(4096 + 248));
+			// The carried over credit is only added to the allowance once
+			assert_eq!(Voting::carried_over_credit(1, &1), 0);
+			assert_eq!(CarriedOverCredit::<Test>::iter().count(), 0);
+
+			// Votes from the previous election do not prevent voting again
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Military, 20));
+			assert_eq!(BudgetDistribution::<Test>::get(Department::Education, 2), None);
+		})
+	}
+
+	#[test]
+	fn voting_against_departments() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 2));
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Military, 30));
+
+			// Opposing a department costs the same quadratic credit as funding it
+			assert_ok!(Voting::vote_against(RuntimeOrigin::signed(2), Department::Military, 20));
+			System::assert_last_event(
+				Event::CitizenOpposed { who: 2, department: Department::Military, amount: 20 }
+					.into(),
+			);
+			assert_eq!(Voting::get_citizen_voting_credit(&2), Some(3696));
+			assert_ok!(Voting::vote_against(RuntimeOrigin::signed(2), Department::Politics, 10));
+
+			// A department cannot be both funded and opposed by the same citizen
+			assert_noop!(
+				Voting::vote_against(RuntimeOrigin::signed(1), Department::Military, 10),
+				Error::<Test>::AlreadyVotedDepartment
+			);
+			assert_eq!(
+				Voting::tally(Department::Military),
+				(FixedU128::from_u32(30), FixedU128::from_u32(20))
+			);
+
+			System::set_block_number(1001);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+
+			// Opposition is netted against support, never going below zero
+			assert_eq!(Voting::balance_of(Department::Military), 10);
+			assert_eq!(Voting::balance_of(Department::Politics), 0);
+		})
+	}
+
+	#[test]
+	fn funding_limits_enforced_on_close() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+
+			// Military can get at most half of the outlay and debt repayment at least a fifth
+			assert_ok!(Voting::set_funding_limits(
+				RuntimeOrigin::root(),
+				Department::Military,
+				Perbill::zero(),
+				Perbill::from_percent(50),
+			));
+			assert_ok!(Voting::set_funding_limits(
+				RuntimeOrigin::root(),
+				Department::RepayingPublicDebt,
+				Perbill::from_percent(20),
+				Perbill::one(),
+			));
+			System::assert_last_event(
+				Event::FundingLimitsSet {
+					department: Department::RepayingPublicDebt,
+					floor: Perbill::from_percent(20),
+					cap: Perbill::one(),
+				}
+				.into(),
+			);
+
+			// Floors cannot be above caps or add up to more than the whole outlay
+			assert_noop!(
+				Voting::set_funding_limits(
+					RuntimeOrigin::root(),
+					Department::Education,
+					Perbill::from_percent(60),
+					Perbill::from_percent(50),
+				),
+				Error::<Test>::InvalidFundingLimits
+			);
+			assert_noop!(
+				Voting::set_funding_limits(
+					RuntimeOrigin::root(),
+					Department::Education,
+					Perbill::from_percent(90),
+					Perbill::one(),
+				),
+				Error::<Test>::InvalidFundingLimits
+			);
+
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Military, 60));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 10));
+
+			System::set_block_number(1001);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+
+			// Out of 70, Military is capped at 35 and debt repayment gets its floor of 14. The
+			// remaining 21 go to Education, the only other department with votes.
+			assert_eq!(Voting::balance_of(Department::Military), 35);
+			assert_eq!(Voting::balance_of(Department::RepayingPublicDebt), 14);
+			assert_eq!(Voting::balance_of(Department::Education), 21);
+		})
+	}
+
+	#[test]
+	fn hybrid_budget_with_legislative_allocation() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+
+			// The legislature cannot allocate before the election opens
+			assert_noop!(
+				Voting::submit_legislative_allocation(
+					RuntimeOrigin::root(),
+					vec![(Department::Military, 1)].try_into().unwrap(),
+				),
+				Error::<Test>::BudgetIsClosed
+			);
+
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_ok!(Voting::open_budget_with_parameters(
+				RuntimeOrigin::root(),
+				ElectionParameters {
+					citizen_share: Perbill::from_percent(60),
+					..Default::default()
+				},
+			));
+
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 50));
+			assert_ok!(Voting::submit_legislative_allocation(
+				RuntimeOrigin::root(),
+				vec![(Department::Military, 1), (Department::Healthcare, 1)].try_into().unwrap(),
+			));
+			System::assert_last_event(Event::LegislativeAllocationSubmitted.into());
+
+			System::set_block_number(1001);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+
+			// Citizens decide on 60% of the outlay of 50, and the legislature on the other 40%
+			System::assert_has_event(
+				Event::DepartmentFunded {
+					budget_id: 0,
+					department: Department::Education,
+					citizen_funds: 30,
+					legislative_funds: 0,
+				}
+				.into(),
+			);
+			assert_eq!(
+				BudgetHistory::<Test>::get(0, Department::Military),
+				Some(DepartmentFunding { citizen: 0, legislative: 10 })
+			);
+			assert_eq!(Voting::balance_of(Department::Education), 30);
+			assert_eq!(Voting::balance_of(Department::Military), 10);
+			assert_eq!(Voting::balance_of(Department::Healthcare), 10);
+		})
+	}
+
+	#[test]
+	fn funding_limits_apply_to_hybrid_budget() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Voting::set_funding_limits(
+				RuntimeOrigin::root(),
+				Department::Military,
+				Perbill::zero(),
+				Perbill::from_percent(50),
+			));
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_ok!(Voting::open_budget_with_parameters(
+				RuntimeOrigin::root(),
+				ElectionParameters {
+					citizen_share: Perbill::from_percent(60),
+					..Default::default()
+				},
+			));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Military, 30));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 20));
+			assert_ok!(Voting::submit_legislative_allocation(
+				RuntimeOrigin::root(),
+				vec![(Department::Military, 1)].try_into().unwrap(),
+			));
+
+			System::set_block_number(1001);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+
+			// Citizens give Military 18 of their 30 and the legislature all of its 20, but
+			// together Military is capped at half of the outlay of 50. It is still funded by both
+			// in the same proportion, and the rest goes to Education.
+			assert_eq!(
+				BudgetHistory::<Test>::get(0, Department::Military),
+				Some(DepartmentFunding { citizen: 11, legislative: 14 })
+			);
+			assert_eq!(
+				BudgetHistory::<Test>::get(0, Department::Education),
+				Some(DepartmentFunding { citizen: 25, legislative: 0 })
+			);
+			assert_eq!(Voting::balance_of(Department::Military), 25);
+			assert_eq!(Voting::balance_of(Department::Education), 25);
+		})
+	}
+
+	#[test]
+	fn hybrid_budget_shares_always_applied() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let parameters = ElectionParameters {
+				citizen_share: Perbill::from_percent(60),
+				..Default::default()
+			};
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+
+			// Without a legislative allocation, its 40% of the outlay is left in the treasury
+			assert_ok!(Voting::open_budget_with_parameters(RuntimeOrigin::root(), parameters));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 50));
+			System::set_block_number(1001);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+			assert_eq!(Voting::balance_of(Department::Education), 30);
+			assert_eq!(Voting::treasury_balance(), TREASURY_POT - 30);
+
+			// What Education did not spend returns to the treasury when the next election opens
+			assert_ok!(Voting::open_budget_with_parameters(RuntimeOrigin::root(), parameters));
+			assert_eq!(Voting::balance_of(Department::Education), 0);
+			assert_eq!(Voting::treasury_balance(), TREASURY_POT);
+
+			// Without citizen votes, the legislature still allocates its share of its allocation
+			assert_ok!(Voting::submit_legislative_allocation(
+				RuntimeOrigin::root(),
+				vec![(Department::Military, 10), (Department::Healthcare, 10)]
+					.try_into()
+					.unwrap(),
+			));
+			System::set_block_number(2002);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+			assert_eq!(
+				BudgetHistory::<Test>::get(1, Department::Military),
+				Some(DepartmentFunding { citizen: 0, legislative: 4 })
+			);
+			assert_eq!(Voting::balance_of(Department::Healthcare), 4);
+		})
+	}
+
+	#[test]
+	fn total_outlay_decided_by_median() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+
+			for citizen in 1..=3 {
+				assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), citizen));
+			}
+			assert_ok!(Voting::open_budget_with_parameters(
+				RuntimeOrigin::root(),
+				ElectionParameters {
+					outlay_range: Some(OutlayRange { min: 100, max: 1_000 }),
+					..Default::default()
+				},
+			));
+
+			// Citizens split the budget evenly between two departments
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 10));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(2), Department::Healthcare, 10));
+
+			assert_noop!(
+				Voting::propose_outlay(RuntimeOrigin::signed(1), 1_001),
+				Error::<Test>::OutlayOutOfRange
+			);
+			assert_ok!(Voting::propose_outlay(RuntimeOrigin::signed(1), 200));
+			assert_ok!(Voting::propose_outlay(RuntimeOrigin::signed(2), 400));
+			// An extreme proposal does not move the median
+			assert_ok!(Voting::propose_outlay(RuntimeOrigin::signed(3), 1_000));
+			System::assert_last_event(Event::OutlayProposed { who: 3, amount: 1_000 }.into());
+			assert_noop!(
+				Voting::propose_outlay(RuntimeOrigin::signed(3), 100),
+				Error::<Test>::AlreadyProposedOutlay
+			);
+			assert_eq!(Voting::median_outlay(), Some(400));
+
+			System::set_block_number(1001);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+			System::assert_has_event(Event::OutlayDecided { budget_id: 0, outlay: 400 }.into());
+
+			assert_eq!(Voting::balance_of(Department::Education), 200);
+			assert_eq!(Voting::balance_of(Department::Healthcare), 200);
+		})
+	}
+
+	#[test]
+	fn outlay_not_on_ballot() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_noop!(
+				Voting::open_budget_with_parameters(
+					RuntimeOrigin::root(),
+					ElectionParameters {
+						outlay_range: Some(OutlayRange { min: 2, max: 1 }),
+						..Default::default()
+					},
+				),
+				Error::<Test>::InvalidOutlayRange
+			);
+
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			assert_noop!(
+				Voting::propose_outlay(RuntimeOrigin::signed(1), 100),
+				Error::<Test>::OutlayNotOnBallot
+			);
+		})
+	}
+
+	#[test]
+	fn aggregation_rules_compared_on_same_ballots() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+
+			for citizen in 1..=3 {
+				assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), citizen));
+			}
+			assert_ok!(Voting::open_budget_with_parameters(
+				RuntimeOrigin::root(),
+				ElectionParameters {
+					aggregator: Aggregator::IndependentMarkets,
+					..Default::default()
+				},
+			));
+
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 30));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Healthcare, 30));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(2), Department::Education, 10));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(3), Department::Military, 10));
+
+			let funds_of = |aggregator: Aggregator, department: Department| {
+				Voting::aggregated_funds(aggregator)
+					.unwrap()
+					.into_iter()
+					.find(|(funded, _)| *funded == department)
+					.map(|(_, funds)| funds)
+			};
+			let funding = |aggregator: Aggregator| {
+				[Department::Education, Department::Healthcare, Department::Military]
+					.map(|department| funds_of(aggregator, department).unwrap())
+			};
+
+			// The outlay of 80 is shared differently by every rule
+			assert_eq!(funding(Aggregator::Sum), [40, 30, 10]);
+			assert_eq!(funding(Aggregator::NormalisedShares), [40, 13, 27]);
+			assert_eq!(funding(Aggregator::IndependentMarkets), [40, 20, 20]);
+
+			// The election uses the rule it was opened with
+			System::set_block_number(1001);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+			assert_eq!(Voting::balance_of(Department::Education), 40);
+			assert_eq!(Voting::balance_of(Department::Healthcare), 20);
+			assert_eq!(Voting::balance_of(Department::Military), 20);
+		})
+	}
+
+	#[test]
+	fn fractional_funding_uses_all_credit() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			assert_noop!(
+				Voting::vote_with_credit(
+					RuntimeOrigin::signed(1),
+					Department::Education,
+					100,
+					true
+				),
+				Error::<Test>::FractionalFundingDisabled
+			);
+
+			System::set_block_number(1001);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+			assert_ok!(Voting::open_budget_with_parameters(
+				RuntimeOrigin::root(),
+				ElectionParameters {
+					funding_units: FundingUnits::Fractional,
+					..Default::default()
+				},
+			));
+
+			// 496 credit is left after funding a whole 60 units, which is not a square
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 60));
+			assert_ok!(Voting::vote_with_credit(
+				RuntimeOrigin::signed(1),
+				Department::Healthcare,
+				496,
+				true
+			));
+			assert_eq!(Voting::get_citizen_voting_credit(&1), Some(0));
+			assert_noop!(
+				Voting::vote_with_credit(
+					RuntimeOrigin::signed(1),
+					Department::Healthcare,
+					0,
+					false
+				),
+				Error::<Test>::AlreadyVotedDepartment
+			);
+
+			// The square root of 496 is a bit over 22.27
+			let (support, _) = Voting::tally(Department::Healthcare);
+			assert!(support > FixedU128::from_rational(2_227, 100));
+			assert!(support < FixedU128::from_rational(2_228, 100));
+
+			// Every whole unit voted is minted, and only the fraction of a unit is left out
+			System::set_block_number(2001);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+			assert_eq!(Voting::balance_of(Department::Education), 60);
+			assert_eq!(Voting::balance_of(Department::Healthcare), 22);
+		})
+	}
+
+	#[test]
+	fn overflowing_credit_is_rejected() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+
+			// 70,000 units of funding would cost more credit than fits in a `u32`
+			assert_noop!(
+				Voting::vote(RuntimeOrigin::signed(1), Department::Education, 70_000),
+				Error::<Test>::ArithmeticOverflow
+			);
+		})
+	}
+
+	#[test]
+	fn fiscal_calendar_deadlines() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+
+			// 1 March 2024 00:00 UTC and 31 March 2024 23:59 UTC
+			let (opens_at, closes_at) = (1_709_251_200_000, 1_711_929_540_000);
+			let calendar = FiscalCalendar { fiscal_year: 2024, opens_at, closes_at };
+			Now::set(opens_at - 1);
+
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_noop!(
+				Voting::open_budget_with_parameters(
+					RuntimeOrigin::root(),
+					ElectionParameters {
+						calendar: Some(FiscalCalendar { closes_at: opens_at, ..calendar }),
+						..Default::default()
+					},
+				),
+				Error::<Test>::InvalidCalendar
+			);
+			assert_ok!(Voting::open_budget_with_parameters(
+				RuntimeOrigin::root(),
+				ElectionParameters { calendar: Some(calendar), ..Default::default() },
+			));
+			assert_noop!(
+				Voting::vote(RuntimeOrigin::signed(1), Department::Education, 10),
+				Error::<Test>::VotingNotStarted
+			);
+
+			// The deadline block no longer applies
+			Now::set(opens_at);
+			System::set_block_number(5_000);
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 10));
+
+			Now::set(closes_at - 1);
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Healthcare, 10));
+			assert_noop!(
+				Voting::close_budget(RuntimeOrigin::signed(1)),
+				Error::<Test>::CannotCloseBeforeDeadline
+			);
+
+			Now::set(closes_at);
+			assert_noop!(
+				Voting::vote(RuntimeOrigin::signed(1), Department::Military, 10),
+				Error::<Test>::CannotVotePastDeadline
+			);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+			assert_eq!(Voting::balance_of(Department::Education), 10);
+			assert_eq!(Voting::balance_of(Department::Healthcare), 10);
+		})
+	}
+
+	#[test]
+	fn recurring_elections() {
+		new_test_ext().execute_with(|| {
+			let run_to_block = |n: u64| {
+				while System::block_number() < n {
+					System::set_block_number(System::block_number() + 1);
+					Voting::on_initialize(System::block_number());
+				}
+			};
+			let is_open = || Voting::budget_info().map_or(false, |budget_info| budget_info.is_open);
+			System::set_block_number(1);
+
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_noop!(
+				Voting::skip_next_election(RuntimeOrigin::root()),
+				Error::<Test>::CannotSchedule
+			);
+			assert_ok!(Voting::set_recurrence(
+				RuntimeOrigin::root(),
+				Some(ElectionRecurrence {
+					period: 2_000,
+					offset: 10,
+					parameters: Default::default()
+				}),
+			));
+			System::assert_last_event(Event::NextElectionScheduled { at: 10 }.into());
+
+			// The first election opens at the offset, and closes when voting is over
+			run_to_block(9);
+			assert!(!is_open());
+			run_to_block(10);
+			assert!(is_open());
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 10));
+			run_to_block(1_010);
+			assert!(!is_open());
+			assert_eq!(Voting::balance_of(Department::Education), 10);
+
+			// Governance skips the next cycle, and then brings it forward
+			assert_ok!(Voting::skip_next_election(RuntimeOrigin::root()));
+			System::assert_last_event(Event::NextElectionScheduled { at: 4_010 }.into());
+			run_to_block(2_010);
+			assert!(!is_open());
+			assert_ok!(Voting::reschedule_next_election(RuntimeOrigin::root(), 3_000));
+			run_to_block(3_000);
+			assert!(is_open());
+			assert_eq!(Voting::budget_info().unwrap().budget_id, 1);
+			assert_eq!(crate::NextElection::<Test>::get(), Some(5_000));
+
+			// An election opened late keeps to the schedule
+			assert_ok!(Voting::extend_budget(
+				RuntimeOrigin::root(),
+				ElectionExtension::Blocks(2_000)
+			));
+			run_to_block(6_000);
+			assert!(!is_open());
+			run_to_block(6_001);
+			assert!(is_open());
+			assert_eq!(crate::NextElection::<Test>::get(), Some(7_000));
+
+			assert_ok!(Voting::set_recurrence(RuntimeOrigin::root(), None));
+			assert_eq!(crate::NextElection::<Test>::get(), None);
+		})
+	}
+
+	#[test]
+	fn delegated_admin_origins() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+
+			// The civil registry registers citizens, but cannot run elections
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::signed(100), 1));
+			assert_noop!(
+				Voting::register_citizen(RuntimeOrigin::signed(200), 2),
+				DispatchError::BadOrigin
+			);
+			assert_noop!(Voting::open_budget(RuntimeOrigin::signed(100)), DispatchError::BadOrigin);
+
+			// The treasury sets funding limits
+			assert_noop!(
+				Voting::set_funding_limits(
+					RuntimeOrigin::signed(200),
+					Department::Education,
+					Perbill::zero(),
+					Perbill::from_percent(50),
+				),
+				DispatchError::BadOrigin
+			);
+			assert_ok!(Voting::set_funding_limits(
+				RuntimeOrigin::signed(300),
+				Department::Education,
+				Perbill::zero(),
+				Perbill::from_percent(50),
+			));
+
+			// The electoral commission opens elections
+			assert_ok!(Voting::open_budget(RuntimeOrigin::signed(200)));
+			assert_ok!(Voting::deregister_citizen(RuntimeOrigin::signed(100), 1));
+		})
+	}
+
+	#[test]
+	fn election_admin_extends_cancels_and_certifies() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_ok!(Voting::open_budget(RuntimeOrigin::signed(200)));
+
+			// Voting is extended past the original deadline at block 1001
+			assert_noop!(
+				Voting::extend_budget(RuntimeOrigin::signed(1), ElectionExtension::Blocks(100)),
+				DispatchError::BadOrigin
+			);
+			assert_noop!(
+				Voting::extend_budget(RuntimeOrigin::signed(200), ElectionExtension::Millis(100)),
+				Error::<Test>::InvalidExtension
+			);
+			assert_ok!(Voting::extend_budget(
+				RuntimeOrigin::signed(200),
+				ElectionExtension::Blocks(100)
+			));
+			System::set_block_number(1_050);
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 10));
+
+			// Results can only be certified once the election is closed
+			assert_noop!(
+				Voting::certify_budget(RuntimeOrigin::signed(200), 0),
+				Error::<Test>::CannotCertify
+			);
+			System::set_block_number(1_101);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+			assert_ok!(Voting::certify_budget(RuntimeOrigin::signed(200), 0));
+			assert_eq!(CertifiedBudgets::<Test>::get(0), Some(1_101));
+			assert_noop!(
+				Voting::certify_budget(RuntimeOrigin::signed(200), 0),
+				Error::<Test>::CannotCertify
+			);
+
+			// A cancelled election funds nothing and has no results to certify
+			assert_ok!(Voting::open_budget(RuntimeOrigin::signed(200)));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 10));
+			assert_ok!(Voting::cancel_budget(RuntimeOrigin::signed(200)));
+			System::assert_has_event(Event::BudgetCancelled { budget_id: 1 }.into());
+			assert_eq!(Voting::balance_of(Department::Education), 0);
+			assert_noop!(
+				Voting::certify_budget(RuntimeOrigin::signed(200), 1),
+				Error::<Test>::CannotCertify
+			);
+			assert_noop!(
+				Voting::cancel_budget(RuntimeOrigin::signed(200)),
+				Error::<Test>::BudgetIsClosed
+			);
+		})
+	}
+
+	#[test]
+	fn governance_parameters_change_between_elections() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+
+			// Defaults come from the configuration
+			let old = Voting::parameters();
+			assert_eq!(old.given_voting_credit, 4096);
+			assert_eq!(old.budget_lifetime, 1_000);
+
+			let new = GovernanceParameters { given_voting_credit: 100, budget_lifetime: 10, ..old };
+			assert_noop!(
+				Voting::set_parameters(
+					RuntimeOrigin::signed(200),
+					GovernanceParameters { budget_lifetime: 0, ..new }
+				),
+				Error::<Test>::InvalidParameters
+			);
+			assert_ok!(Voting::set_parameters(RuntimeOrigin::signed(200), new));
+			System::assert_last_event(Event::ParametersSet { old, new }.into());
+
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			assert_eq!(Voting::get_citizen_voting_credit(&1), Some(100));
+			assert_eq!(Voting::budget_info().unwrap().deadline, 11);
+
+			// The election in progress keeps the parameters it was opened with
+			assert_noop!(
+				Voting::set_parameters(RuntimeOrigin::signed(200), old),
+				Error::<Test>::ElectionInProgress
+			);
+		})
+	}
+
+	#[test]
+	fn deadline_past_last_block_is_rejected() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(u64::MAX - 10);
+			assert_noop!(
+				Voting::open_budget(RuntimeOrigin::root()),
+				Error::<Test>::ArithmeticOverflow
+			);
+		})
+	}
+
+	#[test]
+	fn citizen_count_is_capped() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let parameters = Voting::parameters();
+			assert_ok!(Voting::set_parameters(
+				RuntimeOrigin::root(),
+				GovernanceParameters { max_registered_citizens: 2, ..parameters }
+			));
+
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 2));
+			assert_eq!(Voting::citizen_count(), 2);
+			assert_noop!(
+				Voting::register_citizen(RuntimeOrigin::root(), 3),
+				Error::<Test>::TooManyCitizens
+			);
+
+			// Deregistering frees a place
+			assert_ok!(Voting::deregister_citizen(RuntimeOrigin::root(), 1));
+			assert_eq!(Voting::citizen_count(), 1);
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 3));
+			assert_eq!(Voting::citizen_count(), 2);
+		})
+	}
+
+	#[test]
+	fn budget_election_count_is_capped() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			let parameters = Voting::parameters();
+			assert_ok!(Voting::set_parameters(
+				RuntimeOrigin::root(),
+				GovernanceParameters { max_budget_elections: 2, ..parameters }
+			));
+
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			assert_ok!(Voting::cancel_budget(RuntimeOrigin::root()));
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			assert_ok!(Voting::cancel_budget(RuntimeOrigin::root()));
+			assert_noop!(
+				Voting::open_budget(RuntimeOrigin::root()),
+				Error::<Test>::TooManyBudgetElections
+			);
+
+			// Raising the limit allows further elections
+			assert_ok!(Voting::set_parameters(RuntimeOrigin::root(), parameters));
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			assert_eq!(Voting::budget_info().unwrap().budget_id, 2);
+		})
+	}
+
+	#[test]
+	fn citizen_records() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(3);
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::signed(100), 1));
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 2));
+			assert_ok!(Voting::set_citizen_region(RuntimeOrigin::signed(100), 1, Some(7)));
+			assert_eq!(Voting::citizens_in_region(7), vec![1]);
+
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 10));
+			let citizen_info = Voting::citizen_info(&1).unwrap();
+			assert_eq!(citizen_info.registered_at, 3);
+			assert_eq!(citizen_info.registered_by, Some(100));
+			assert_eq!(citizen_info.last_voted_budget, Some(0));
+			assert_eq!(Voting::citizen_info(&2).unwrap().registered_by, None);
+
+			// Suspended citizens cannot vote until their suspension is lifted
+			assert_ok!(Voting::set_citizen_suspended(RuntimeOrigin::signed(100), 2, true));
+			assert_noop!(
+				Voting::vote(RuntimeOrigin::signed(2), Department::Education, 10),
+				Error::<Test>::CitizenSuspended
+			);
+			assert_ok!(Voting::set_citizen_suspended(RuntimeOrigin::signed(100), 2, false));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(2), Department::Education, 10));
+			assert_noop!(
+				Voting::set_citizen_suspended(RuntimeOrigin::signed(100), 3, true),
+				Error::<Test>::CitizenNotRegistered
+			);
+		})
+	}
+
+	#[test]
+	fn citizens_migrate_to_records() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			migrations::v0::RegisteredCitizens::<Test>::insert(1, 50);
+			migrations::v0::RegisteredCitizens::<Test>::insert(2, 0);
+			migrations::v0::RegisteredCitizens::<Test>::insert(3, 0);
+			migrations::v0::CurrentBudgetElection::<Test>::put(migrations::v0::BudgetInfo {
+				budget_id: 3,
+				deadline: 100,
+				is_open: true,
+			});
+			migrations::v0::BudgetDistribution::<Test>::insert(Department::Education, 1, 7);
+			migrations::v0::BudgetDistribution::<Test>::insert(Department::Healthcare, 1, 2);
+			migrations::v0::BudgetDistribution::<Test>::insert(Department::Education, 3, 5);
+			// Citizen 4 was deregistered after voting
+			migrations::v0::BudgetDistribution::<Test>::insert(Department::Military, 4, 9);
+
+			migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+			assert_eq!(StorageVersion::get::<Voting>(), 1);
+			assert_eq!(Voting::citizen_count(), 3);
+			assert_eq!(Voting::get_citizen_voting_credit(&1), Some(50));
+			assert_eq!(Voting::citizen_info(&2).unwrap().last_voted_budget, None);
+
+			// The open election keeps its id and its votes, and who cast them
+			let budget_info = Voting::budget_info().unwrap();
+			assert_eq!((budget_info.budget_id, budget_info.deadline), (3, 100));
+			assert!(budget_info.is_open && budget_info.asset.is_none());
+			assert_eq!(BudgetDistribution::<Test>::get(Department::Education, 1), Some(7));
+			assert_eq!(BudgetDistribution::<Test>::get(Department::Military, 4), None);
+			assert_eq!(Voting::voter_count(), 2);
+			assert_eq!(Voting::citizen_info(&1).unwrap().last_voted_budget, Some(3));
+			assert_eq!(Voting::citizen_info(&3).unwrap().last_voted_budget, Some(3));
+
+			// So the votes are counted when it closes
+			System::set_block_number(100);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+			assert_eq!(Voting::balance_of(Department::Education), 12);
+			assert_eq!(Voting::balance_of(Department::Healthcare), 2);
+			assert_eq!(Voting::balance_of(Department::Military), 0);
+			assert_eq!(BudgetDistribution::<Test>::iter().count(), 0);
+		})
+	}
+
+	#[test]
+	fn votes_of_closed_election_cleared_by_migration() {
+		new_test_ext().execute_with(|| {
+			migrations::v0::CurrentBudgetElection::<Test>::put(migrations::v0::BudgetInfo {
+				budget_id: 3,
+				deadline: 100,
+				is_open: false,
+			});
+			migrations::v0::BudgetDistribution::<Test>::insert(Department::Education, 1, 7);
+
+			migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+			assert_eq!(BudgetDistribution::<Test>::iter().count(), 0);
+
+			// The next election does not reuse the id of the migrated one
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			assert_eq!(Voting::budget_info().unwrap().budget_id, 4);
+		})
+	}
+
+	#[test]
+	fn bulk_registration() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 2));
+
+			assert_ok!(Voting::register_citizens(
+				RuntimeOrigin::signed(100),
+				vec![1, 2, 3].try_into().unwrap()
+			));
+			System::assert_last_event(
+				Event::CitizensRegistered { registered: 2, already_registered: vec![2] }.into(),
+			);
+			assert_eq!(Voting::citizen_count(), 3);
+
+			assert_ok!(Voting::deregister_citizens(
+				RuntimeOrigin::signed(100),
+				vec![1, 4].try_into().unwrap()
+			));
+			System::assert_last_event(
+				Event::CitizensDeregistered { deregistered: 1, not_found: vec![4] }.into(),
+			);
+			assert_eq!(Voting::citizen_count(), 2);
+			assert_noop!(
+				Voting::register_citizens(RuntimeOrigin::signed(200), vec![5].try_into().unwrap()),
+				DispatchError::BadOrigin
+			);
+		})
+	}
+
+	#[test]
+	fn genesis_citizens() {
+		let genesis = RuntimeGenesisConfig {
+			voting: VotingConfig { citizens: vec![1, 2], ..Default::default() },
+			..Default::default()
+		};
+		sp_io::TestExternalities::from(genesis.build_storage().unwrap()).execute_with(|| {
+			assert_eq!(Voting::citizen_count(), 2);
+			assert_eq!(Voting::get_citizen_voting_credit(&1), Some(0));
+		})
+	}
+
+	#[test]
+	fn genesis_election() {
+		let genesis = RuntimeGenesisConfig {
+			voting: VotingConfig {
+				citizens: vec![1],
+				funding_limits: vec![(
+					Department::Military,
+					Perbill::zero(),
+					Perbill::from_percent(10),
+				)],
+				budget: Some(ElectionParameters {
+					aggregator: Aggregator::NormalisedShares,
+					..Default::default()
+				}),
+			},
+			..Default::default()
+		};
+		sp_io::TestExternalities::from(genesis.build_storage().unwrap()).execute_with(|| {
+			System::set_block_number(1);
+			let budget_info = Voting::budget_info().unwrap();
+			assert!(budget_info.is_open);
+			assert_eq!(budget_info.parameters.aggregator, Aggregator::NormalisedShares);
+			assert_eq!(
+				FundingLimits::<Test>::get(Department::Military).cap,
+				Perbill::from_percent(10)
+			);
+
+			// Genesis citizens can vote straight away
+			assert_eq!(Voting::get_citizen_voting_credit(&1), Some(4096));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 10));
+		})
+	}
+
+	#[test]
+	fn registration_applications() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), 5, 100));
+			assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), 6, 100));
+
+			// Applying holds the deposit
+			let evidence = Some(H256::repeat_byte(1));
+			assert_ok!(Voting::request_registration(RuntimeOrigin::signed(5), evidence));
+			assert_noop!(
+				Voting::request_registration(RuntimeOrigin::signed(5), None),
+				Error::<Test>::AlreadyApplied
+			);
+			assert_ok!(Voting::request_registration(RuntimeOrigin::signed(6), None));
+			assert_eq!(Balances::free_balance(5), 90);
+			assert_eq!(Balances::reserved_balance(5), 10);
+			assert_eq!(Voting::pending_applications().len(), 2);
+
+			// Approving registers the applicant and releases the deposit
+			assert_noop!(
+				Voting::approve_registration(RuntimeOrigin::signed(5), 5),
+				DispatchError::BadOrigin
+			);
+			assert_ok!(Voting::approve_registration(RuntimeOrigin::signed(100), 5));
+			assert!(Voting::citizen_info(&5).is_some());
+			assert_eq!(Balances::free_balance(5), 100);
+			assert_noop!(
+				Voting::request_registration(RuntimeOrigin::signed(5), None),
+				Error::<Test>::CitizenAlreadyRegistered
+			);
+
+			// Rejecting slashes the deposit
+			assert_ok!(Voting::reject_registration(RuntimeOrigin::signed(100), 6));
+			System::assert_last_event(Event::RegistrationRejected { who: 6, slashed: 10 }.into());
+			assert_eq!(Balances::free_balance(6), 90);
+			assert_eq!(Balances::reserved_balance(6), 0);
+			assert_eq!(Voting::citizen_info(&6), None);
+			assert!(Voting::pending_applications().is_empty());
+			assert_noop!(
+				Voting::reject_registration(RuntimeOrigin::signed(100), 6),
+				Error::<Test>::ApplicationNotFound
+			);
+
+			// Applicants registered in another way get their deposit back
+			assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), 7, 100));
+			assert_ok!(Voting::request_registration(RuntimeOrigin::signed(7), None));
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::signed(100), 7));
+			assert_eq!(Balances::free_balance(7), 100);
+			assert!(Voting::pending_applications().is_empty());
+			assert_noop!(
+				Voting::approve_registration(RuntimeOrigin::signed(100), 7),
+				Error::<Test>::ApplicationNotFound
+			);
+
+			// Applicants can also withdraw their application
+			assert_ok!(Voting::request_registration(RuntimeOrigin::signed(6), None));
+			assert_ok!(Voting::cancel_registration_request(RuntimeOrigin::signed(6)));
+			System::assert_last_event(Event::RegistrationCancelled { who: 6 }.into());
+			assert_eq!(Balances::free_balance(6), 90);
+			assert_eq!(Balances::reserved_balance(6), 0);
+			assert_noop!(
+				Voting::cancel_registration_request(RuntimeOrigin::signed(6)),
+				Error::<Test>::ApplicationNotFound
+			);
+		})
+	}
+
+	#[test]
+	fn citizenship_claims() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+
+			// Only accounts with a verified identity can claim citizenship
+			assert_noop!(
+				Voting::claim_citizenship(RuntimeOrigin::signed(5)),
+				Error::<Test>::IdentityNotVerified
+			);
+			VerifiedIdentities::set(vec![5]);
+			assert_ok!(Voting::claim_citizenship(RuntimeOrigin::signed(5)));
+			let info = Voting::citizen_info(&5).unwrap();
+			assert_eq!(info.registered_by, None);
+			assert_eq!(Voting::citizen_count(), 1);
+			assert_noop!(
+				Voting::claim_citizenship(RuntimeOrigin::signed(5)),
+				Error::<Test>::CitizenAlreadyRegistered
+			);
+
+			// They can only vote while their identity stays verified
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(5), Department::Education, 1));
+			VerifiedIdentities::set(vec![]);
+			assert_noop!(
+				Voting::vote(RuntimeOrigin::signed(5), Department::Healthcare, 1),
+				Error::<Test>::IdentityNotVerified
+			);
+
+			// Unless the registrar registers them after they are deregistered
+			assert_ok!(Voting::cancel_budget(RuntimeOrigin::root()));
+			assert_ok!(Voting::deregister_citizen(RuntimeOrigin::root(), 5));
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 5));
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(5), Department::Healthcare, 1));
+		})
+	}
+
+	#[test]
+	fn citizenship_credentials() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+
+			// Registering issues a credential and deregistering revokes it
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 2));
+			assert_eq!(Credentials::get(), vec![(1, 0), (2, 1)]);
+			assert_ok!(Voting::deregister_citizen(RuntimeOrigin::root(), 1));
+			assert_eq!(Credentials::get(), vec![(2, 1)]);
+
+			// Serial numbers are never reused
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_eq!(Credentials::get(), vec![(2, 1), (1, 2)]);
+
+			// Only holders of a credential are eligible to vote
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			Credentials::set(vec![(2, 1)]);
+			assert_noop!(
+				Voting::vote(RuntimeOrigin::signed(1), Department::Education, 10),
+				Error::<Test>::NoCitizenshipCredential
+			);
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(2), Department::Education, 10));
+
+			// The registrar issues credentials to citizens who lack one
+			assert_noop!(
+				Voting::issue_credential(RuntimeOrigin::signed(200), 1),
+				DispatchError::BadOrigin
+			);
+			assert_noop!(
+				Voting::issue_credential(RuntimeOrigin::signed(100), 3),
+				Error::<Test>::CitizenNotRegistered
+			);
+			assert_noop!(
+				Voting::issue_credential(RuntimeOrigin::signed(100), 2),
+				Error::<Test>::CredentialAlreadyHeld
+			);
+			assert_ok!(Voting::issue_credential(RuntimeOrigin::signed(100), 1));
+			System::assert_last_event(Event::CredentialIssued { who: 1, serial: 3 }.into());
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 10));
+
+			// Or to many at once, skipping those who are not citizens or already hold one
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 4));
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 5));
+			Credentials::set(vec![(2, 1), (1, 3), (4, 4)]);
+			assert_ok!(Voting::issue_credentials(
+				RuntimeOrigin::signed(100),
+				vec![3, 4, 5, 2].try_into().unwrap()
+			));
+			System::assert_last_event(Event::CredentialIssued { who: 5, serial: 6 }.into());
+			assert_eq!(Credentials::get(), vec![(2, 1), (1, 3), (4, 4), (5, 6)]);
+		})
+	}
+
+	#[test]
+	fn credit_updated_over_several_blocks() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			CitizensPerBlock::set(2);
+			CarryoverFraction::set(Perbill::from_percent(50));
+			for citizen in 1..=5 {
+				assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), citizen));
+			}
+
+			// Only the first batch of citizens receive their credit when the election opens
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			let with_credit = || {
+				(1..=5).filter(|citizen| Voting::get_citizen_voting_credit(citizen) == Some(4096))
+			};
+			assert_eq!(with_credit().count(), 2);
+			assert_noop!(
+				Voting::vote(RuntimeOrigin::signed(1), Department::Education, 10),
+				Error::<Test>::CreditUpdateInProgress
+			);
+
+			// Those registering in the meantime receive their credit like everyone else
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 6));
+			assert_eq!(Voting::get_citizen_voting_credit(&6), Some(4096));
+
+			// The rest receive it in the blocks that follow, and then voting starts
+			System::set_block_number(2);
+			Voting::on_initialize(2);
+			assert_noop!(
+				Voting::vote(RuntimeOrigin::signed(1), Department::Education, 10),
+				Error::<Test>::CreditUpdateInProgress
+			);
+			System::set_block_number(3);
+			Voting::on_initialize(3);
+			System::assert_last_event(Event::CreditAllocated { budget_id: 0 }.into());
+			assert_eq!(with_credit().count(), 5);
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 10));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Healthcare, 10));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(2), Department::Healthcare, 10));
+			assert_eq!(Voting::voter_count(), 2);
+
+			// The votes are counted a voter per block, and the departments funded once they all are
+			VotersPerBlock::set(1);
+			System::set_block_number(1001);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+			assert!(!Voting::budget_info().unwrap().is_open);
+			assert_eq!(Voting::balance_of(Department::Healthcare), 0);
+
+			// The next election waits for the votes to be counted and the unspent credit settled
+			assert_noop!(
+				Voting::open_budget(RuntimeOrigin::root()),
+				Error::<Test>::CreditUpdateInProgress
+			);
+			for n in 1002..=1005 {
+				System::set_block_number(n);
+				Voting::on_initialize(n);
+			}
+			System::assert_has_event(Event::CreditSettled { budget_id: 0 }.into());
+			System::assert_last_event(Event::BudgetClosed.into());
+			assert_eq!(Voting::balance_of(Department::Healthcare), 20);
+			assert_eq!(Voting::balance_of(Department::Education), 10);
+			assert_eq!(
+				Voting::tally(Department::Healthcare),
+				(FixedU128::from_u32(0), FixedU128::from_u32(0))
+			);
+			assert!((1..=6).all(|citizen| Voting::carried_over_credit(1, &citizen) == 1024));
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+		})
+	}
+
+	#[test]
+	fn failed_funding_closes_election() {
+		new_test_ext().execute_with(|| {
+			let run_to_block = |n: u64| {
+				while System::block_number() < n {
+					System::set_block_number(System::block_number() + 1);
+					Voting::on_initialize(System::block_number());
+				}
+			};
+			System::set_block_number(1);
+
+			// Departments cannot be funded in an asset with such a high minimum balance
+			BudgetAssetBase::set(Some(100));
+			assert_ok!(Assets::force_create(RuntimeOrigin::root(), 100.into(), 1, true, 1_000));
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_ok!(Voting::set_recurrence(
+				RuntimeOrigin::root(),
+				Some(ElectionRecurrence {
+					period: 2_000,
+					offset: 10,
+					parameters: Default::default()
+				}),
+			));
+			run_to_block(10);
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 10));
+
+			// The election is closed without funding any department
+			run_to_block(1_010);
+			System::assert_has_event(
+				Event::BudgetFundingFailed { budget_id: 0, error: TokenError::BelowMinimum.into() }
+					.into(),
+			);
+			System::assert_last_event(Event::BudgetClosed.into());
+			assert!(!Voting::budget_info().unwrap().is_open);
+			assert_eq!(BudgetHistory::<Test>::get(0, Department::Education), None);
+			assert_eq!(Voting::treasury_balance(), TREASURY_POT);
+
+			// Recurring elections carry on
+			assert_eq!(crate::NextElection::<Test>::get(), Some(2_010));
+			run_to_block(2_010);
+			assert_eq!(Voting::budget_info().unwrap().budget_id, 1);
+		})
+	}
+
+	#[test]
+	fn deregistered_citizen_votes_withdrawn() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 2));
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 10));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(2), Department::Healthcare, 20));
+			assert_eq!(Voting::voter_count(), 2);
+
+			assert_ok!(Voting::deregister_citizen(RuntimeOrigin::root(), 2));
+			assert_eq!(Voting::voter_count(), 1);
+			assert_eq!(
+				Voting::tally(Department::Healthcare),
+				(FixedU128::from_u32(0), FixedU128::from_u32(0))
+			);
+
+			System::set_block_number(1001);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+			assert_eq!(Voting::balance_of(Department::Education), 10);
+			assert_eq!(Voting::balance_of(Department::Healthcare), 0);
+		})
+	}
+
+	#[test]
+	fn outlay_paid_from_treasury() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Voting::register_citizen(RuntimeOrigin::root(), 1));
+			assert_ok!(Voting::open_budget(RuntimeOrigin::root()));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Education, 30));
+			assert_ok!(Voting::vote(RuntimeOrigin::signed(1), Department::Healthcare, 50));
+
+			// The treasury can only spend half of the outlay, keeping its existential deposit
+			let treasury = Voting::treasury_account_id();
+			assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), treasury, 41));
+			System::set_block_number(1001);
+			assert_ok!(Voting::close_budget(RuntimeOrigin::signed(1)));
+			System::assert_has_event(Event::OutlayCapped { budget_id: 0, pot: 40 }.into());
+			assert_eq!(Voting::balance_of(Department::Education), 15);
+			assert_eq!(Voting::balance_of(Department::Healthcare), 25);
+			assert_eq!(Voting::treasury_balance(), 1);
+			assert_eq!(
+				BudgetHistory::<Test>::get(0, Department::Healthcare),
+				Some(DepartmentFunding { citizen: 25, legislative: 0 })
+			);
+		})
+	}
 }
@@ -0,0 +1,264 @@
+//! Weights for pallet_voting.
+//!
+//! These are hand-written estimates, not benchmark results: they count the storage accesses of
+//! each call and add a rough execution time. Replace them with the output of the pallet's
+//! benchmarks, run on reference hardware, before relying on them:
+//!
+//! ```text
+//! ./target/release/node-template benchmark pallet \
+//! 	--chain dev \
+//! 	--pallet pallet_voting \
+//! 	--extrinsic '*' \
+//! 	--steps 50 \
+//! 	--repeat 20 \
+//! 	--output pallets/voting/src/weights.rs \
+//! 	--template <substrate>/.maintain/frame-weight-template.hbs
+//! ```
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use core::marker::PhantomData;
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+
+/// Weight functions needed for pallet_voting.
+pub trait WeightInfo {
+	fn register_citizens(n: u32) -> Weight;
+	fn deregister_citizens(n: u32) -> Weight;
+	fn open_budget() -> Weight;
+	fn close_budget() -> Weight;
+	fn tally_votes(v: u32) -> Weight;
+	fn update_credit(n: u32) -> Weight;
+	fn issue_credential() -> Weight;
+	fn issue_credentials(n: u32) -> Weight;
+	fn vote() -> Weight;
+	fn vote_against() -> Weight;
+	fn vote_with_credit() -> Weight;
+	fn propose_outlay() -> Weight;
+	fn set_funding_limits() -> Weight;
+	fn submit_legislative_allocation() -> Weight;
+}
+
+/// Estimated weights for pallet_voting, using the runtime's database weights.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Reads the current election, the parameters and the citizen count, then reads and writes
+	/// the record of each of the `n` citizens, mints their credit asset if the election is open,
+	/// and mints the NFT holding their credential.
+	fn register_citizens(n: u32) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(Weight::from_parts(40_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().reads_writes(7_u64, 8_u64).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	/// Reads the current election and the citizen count, then removes the record, claim, carried
+	/// over credit, credit asset and votes of each of the `n` citizens, and burns the NFT holding
+	/// their credential.
+	fn deregister_citizens(n: u32) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(Weight::from_parts(40_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(
+				T::DbWeight::get().reads_writes(48_u64, 51_u64).saturating_mul(n.into()),
+			)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	/// Creates the budget and credit assets, returns what each of the 10 departments has left to
+	/// the treasury, and clears their legislative allocation.
+	fn open_budget() -> Weight {
+		Weight::from_parts(50_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(28_u64))
+			.saturating_add(T::DbWeight::get().writes(47_u64))
+	}
+
+	/// Reads the counted votes, funds each of the 10 departments and records their funding.
+	fn close_budget() -> Weight {
+		Weight::from_parts(100_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(25_u64))
+			.saturating_add(T::DbWeight::get().writes(32_u64))
+	}
+
+	/// Takes the votes of each of the `v` voters for each of the 10 departments and their
+	/// proposed outlay, and counts them.
+	fn tally_votes(v: u32) -> Weight {
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(Weight::from_parts(5_000_000, 0).saturating_mul(v.into()))
+			.saturating_add(T::DbWeight::get().reads(42_u64).saturating_mul(v.into()))
+			.saturating_add(T::DbWeight::get().writes(42_u64).saturating_mul(v.into()))
+	}
+
+	/// Reads the pending update, then mints, burns or carries over the credit of each of the `n`
+	/// citizens.
+	fn update_credit(n: u32) -> Weight {
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(Weight::from_parts(20_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads_writes(2_u64, 1_u64))
+			.saturating_add(T::DbWeight::get().reads_writes(4_u64, 3_u64).saturating_mul(n.into()))
+	}
+
+	/// Reads the citizen's record and whether they hold a credential, then mints the NFT holding
+	/// it with the next serial number.
+	fn issue_credential() -> Weight {
+		Weight::from_parts(40_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().reads_writes(5_u64, 6_u64))
+	}
+
+	/// Reads the record of each of the `n` citizens and whether they hold a credential, then
+	/// mints the NFT holding it with the next serial number.
+	fn issue_credentials(n: u32) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(Weight::from_parts(40_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads_writes(8_u64, 6_u64).saturating_mul(n.into()))
+	}
+	/// Checks the election is open and the citizen eligible and has not voted on the department,
+	/// then records their vote and remaining credit, and burns it from their credit asset.
+	fn vote() -> Weight {
+		Weight::from_parts(40_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(16_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+
+	/// Like `vote`, recording the vote against the department.
+	fn vote_against() -> Weight {
+		Weight::from_parts(40_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(16_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+
+	/// Like `vote`, taking the square root of the credit spent.
+	fn vote_with_credit() -> Weight {
+		Weight::from_parts(50_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(16_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+
+	/// Checks the election is open and the citizen eligible and has not proposed an outlay yet,
+	/// then records their proposal.
+	fn propose_outlay() -> Weight {
+		Weight::from_parts(30_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(10_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+
+	/// Reads the floors of the other 9 departments, then records the department's limits.
+	fn set_funding_limits() -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(10_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+
+	/// Reads the current election, then replaces the allocation of each of the 10 departments.
+	fn submit_legislative_allocation() -> Weight {
+		Weight::from_parts(30_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(11_u64))
+			.saturating_add(T::DbWeight::get().writes(20_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn register_citizens(n: u32) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(Weight::from_parts(40_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(
+				RocksDbWeight::get().reads_writes(7_u64, 8_u64).saturating_mul(n.into()),
+			)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+
+	fn deregister_citizens(n: u32) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(Weight::from_parts(40_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(
+				RocksDbWeight::get().reads_writes(48_u64, 51_u64).saturating_mul(n.into()),
+			)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+
+	fn open_budget() -> Weight {
+		Weight::from_parts(50_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(28_u64))
+			.saturating_add(RocksDbWeight::get().writes(47_u64))
+	}
+
+	fn close_budget() -> Weight {
+		Weight::from_parts(100_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(25_u64))
+			.saturating_add(RocksDbWeight::get().writes(32_u64))
+	}
+
+	fn tally_votes(v: u32) -> Weight {
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(Weight::from_parts(5_000_000, 0).saturating_mul(v.into()))
+			.saturating_add(RocksDbWeight::get().reads(42_u64).saturating_mul(v.into()))
+			.saturating_add(RocksDbWeight::get().writes(42_u64).saturating_mul(v.into()))
+	}
+
+	fn update_credit(n: u32) -> Weight {
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(Weight::from_parts(20_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads_writes(2_u64, 1_u64))
+			.saturating_add(
+				RocksDbWeight::get().reads_writes(4_u64, 3_u64).saturating_mul(n.into()),
+			)
+	}
+
+	fn issue_credential() -> Weight {
+		Weight::from_parts(40_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().reads_writes(5_u64, 6_u64))
+	}
+
+	fn issue_credentials(n: u32) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(Weight::from_parts(40_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(
+				RocksDbWeight::get().reads_writes(8_u64, 6_u64).saturating_mul(n.into()),
+			)
+	}
+
+	fn vote() -> Weight {
+		Weight::from_parts(40_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(16_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+
+	fn vote_against() -> Weight {
+		Weight::from_parts(40_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(16_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+
+	fn vote_with_credit() -> Weight {
+		Weight::from_parts(50_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(16_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+
+	fn propose_outlay() -> Weight {
+		Weight::from_parts(30_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(10_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+
+	fn set_funding_limits() -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(10_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+
+	fn submit_legislative_allocation() -> Weight {
+		Weight::from_parts(30_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(11_u64))
+			.saturating_add(RocksDbWeight::get().writes(20_u64))
+	}
+}
@@ -6,6 +6,10 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+use frame_support::{
+	dispatch::DispatchResult,
+	traits::tokens::nonfungibles_v2::{InspectEnumerable, Mutate},
+};
 use pallet_grandpa::AuthorityId as GrandpaId;
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -13,7 +17,8 @@ use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{
-		AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, One, Verify,
+		AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, Convert,
+		IdentifyAccount, NumberFor, One, Verify,
 	},
 	transaction_validity::{TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, MultiSignature,
@@ -27,8 +32,8 @@ use sp_version::RuntimeVersion;
 pub use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{
-		ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness,
-		StorageInfo,
+		AsEnsureOriginWithArg, ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, Get,
+		KeyOwnerProofSystem, Randomness, StorageInfo,
 	},
 	weights::{
 		constants::{
@@ -36,10 +41,16 @@ pub use frame_support::{
 		},
 		IdentityFee, Weight,
 	},
-	StorageValue,
+	PalletId, StorageValue,
 };
 pub use frame_system::Call as SystemCall;
+use frame_system::{EnsureRoot, EnsureSigned};
 pub use pallet_balances::Call as BalancesCall;
+use pallet_identity::Judgement;
+use pallet_nfts::{
+	CollectionConfig, CollectionSetting, CollectionSettings, ItemConfig, ItemSetting, ItemSettings,
+	MintSettings, PalletFeatures,
+};
 pub use pallet_timestamp::Call as TimestampCall;
 use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier};
 #[cfg(any(feature = "std", test))]
@@ -49,6 +60,9 @@ pub use sp_runtime::{Perbill, Permill};
 /// Import the template pallet.
 pub use pallet_template;
 
+/// Import the voting pallet.
+pub use pallet_voting;
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -245,8 +259,8 @@ impl pallet_balances::Config for Runtime {
 	type WeightInfo = pallet_balances::weights::SubstrateWeight<Runtime>;
 	type FreezeIdentifier = ();
 	type MaxFreezes = ();
-	type RuntimeHoldReason = ();
-	type MaxHolds = ();
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type MaxHolds = ConstU32<1>;
 }
 
 parameter_types! {
@@ -274,6 +288,239 @@ impl pallet_template::Config for Runtime {
 	type WeightInfo = pallet_template::weights::SubstrateWeight<Runtime>;
 }
 
+impl pallet_assets::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type AssetId = u32;
+	type AssetIdParameter = codec::Compact<u32>;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDeposit = ConstU128<{ 100 * EXISTENTIAL_DEPOSIT }>;
+	type AssetAccountDeposit = ConstU128<EXISTENTIAL_DEPOSIT>;
+	type MetadataDepositBase = ConstU128<{ 10 * EXISTENTIAL_DEPOSIT }>;
+	type MetadataDepositPerByte = ConstU128<EXISTENTIAL_DEPOSIT>;
+	type ApprovalDeposit = ConstU128<EXISTENTIAL_DEPOSIT>;
+	type StringLimit = ConstU32<50>;
+	/// Keeps voting credit from being transferred.
+	type Freezer = Voting;
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+	type RemoveItemsLimit = ConstU32<1000>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
+parameter_types! {
+	pub const CommissionMotionDuration: BlockNumber = 3 * DAYS;
+	pub MaxCommissionProposalWeight: Weight =
+		Perbill::from_percent(50) * BlockWeights::get().max_block;
+}
+
+/// The electoral commission, whose members jointly run budget elections.
+pub type ElectoralCommissionInstance = pallet_collective::Instance1;
+
+impl pallet_collective::Config<ElectoralCommissionInstance> for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type Proposal = RuntimeCall;
+	type RuntimeEvent = RuntimeEvent;
+	type MotionDuration = CommissionMotionDuration;
+	type MaxProposals = ConstU32<100>;
+	type MaxMembers = ConstU32<100>;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+	type SetMembersOrigin = EnsureRoot<AccountId>;
+	type MaxProposalWeight = MaxCommissionProposalWeight;
+}
+
+/// At least two thirds of the electoral commission.
+pub type CommissionSupermajority =
+	pallet_collective::EnsureProportionAtLeast<AccountId, ElectoralCommissionInstance, 2, 3>;
+
+impl pallet_identity::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type BasicDeposit = ConstU128<{ 10 * EXISTENTIAL_DEPOSIT }>;
+	type FieldDeposit = ConstU128<{ 2 * EXISTENTIAL_DEPOSIT }>;
+	type SubAccountDeposit = ConstU128<{ 2 * EXISTENTIAL_DEPOSIT }>;
+	type MaxSubAccounts = ConstU32<100>;
+	type MaxAdditionalFields = ConstU32<100>;
+	type MaxRegistrars = ConstU32<20>;
+	type Slashed = ();
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type RegistrarOrigin = EnsureRoot<AccountId>;
+	type WeightInfo = pallet_identity::weights::SubstrateWeight<Runtime>;
+}
+
+/// Treats an account as verified once the identity registrar at index `Registrar` has judged
+/// its identity reasonable or known good.
+pub struct IdentityJudgement<Registrar>(sp_std::marker::PhantomData<Registrar>);
+impl<Registrar: Get<u32>> pallet_voting::IdentityVerifier<AccountId>
+	for IdentityJudgement<Registrar>
+{
+	fn is_verified(who: &AccountId) -> bool {
+		Identity::identity(who).map_or(false, |registration| {
+			registration.judgements.iter().any(|(index, judgement)| {
+				*index == Registrar::get() &&
+					matches!(judgement, Judgement::Reasonable | Judgement::KnownGood)
+			})
+		})
+	}
+}
+
+parameter_types! {
+	pub NftFeatures: PalletFeatures = PalletFeatures::all_enabled();
+}
+
+impl pallet_nfts::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type CollectionId = u32;
+	type ItemId = u32;
+	type Currency = Balances;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+	type Locker = ();
+	type CollectionDeposit = ConstU128<{ 100 * EXISTENTIAL_DEPOSIT }>;
+	type ItemDeposit = ConstU128<EXISTENTIAL_DEPOSIT>;
+	type MetadataDepositBase = ConstU128<{ 10 * EXISTENTIAL_DEPOSIT }>;
+	type AttributeDepositBase = ConstU128<{ 10 * EXISTENTIAL_DEPOSIT }>;
+	type DepositPerByte = ConstU128<EXISTENTIAL_DEPOSIT>;
+	type StringLimit = ConstU32<256>;
+	type KeyLimit = ConstU32<64>;
+	type ValueLimit = ConstU32<256>;
+	type ApprovalsLimit = ConstU32<20>;
+	type ItemAttributesApprovalsLimit = ConstU32<20>;
+	type MaxTips = ConstU32<10>;
+	type MaxDeadlineDuration = ConstU32<{ 12 * 30 * DAYS }>;
+	type MaxAttributesPerCall = ConstU32<10>;
+	type Features = NftFeatures;
+	type OffchainSignature = Signature;
+	type OffchainPublic = <Signature as Verify>::Signer;
+	type WeightInfo = pallet_nfts::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type Helper = ();
+}
+
+/// Issues citizenship as a non-transferable item of the NFT collection `Collection`, owned by the
+/// voting pallet, so wallets and explorers can display it. The collection is created with the
+/// first credential, without deposits.
+pub struct SoulboundCitizenship<Collection>(sp_std::marker::PhantomData<Collection>);
+impl<Collection: Get<u32>> SoulboundCitizenship<Collection> {
+	fn ensure_collection() -> DispatchResult {
+		let collection = Collection::get();
+		if pallet_nfts::Collection::<Runtime>::contains_key(collection) {
+			return Ok(())
+		}
+		let owner: AccountId = VotingPalletId::get().into_account_truncating();
+		let config = CollectionConfig {
+			settings: CollectionSettings::from_disabled(
+				CollectionSetting::TransferableItems | CollectionSetting::DepositRequired,
+			),
+			max_supply: None,
+			mint_settings: MintSettings::default(),
+		};
+		Nfts::do_create_collection(
+			collection,
+			owner.clone(),
+			owner.clone(),
+			config,
+			0,
+			pallet_nfts::Event::ForceCreated { collection, owner },
+		)
+	}
+}
+
+impl<Collection: Get<u32>> pallet_voting::CitizenshipCredential<AccountId>
+	for SoulboundCitizenship<Collection>
+{
+	fn issue(who: &AccountId, serial: u32) -> DispatchResult {
+		Self::ensure_collection()?;
+		let config =
+			ItemConfig { settings: ItemSettings::from_disabled(ItemSetting::Transferable.into()) };
+		<Nfts as Mutate<AccountId, ItemConfig>>::mint_into(
+			&Collection::get(),
+			&serial,
+			who,
+			&config,
+			true,
+		)
+	}
+
+	fn revoke(who: &AccountId) -> DispatchResult {
+		let collection = Collection::get();
+		let items: Vec<u32> = Nfts::owned_in_collection(&collection, who).collect();
+		for item in items {
+			<Nfts as Mutate<AccountId, ItemConfig>>::burn(&collection, &item, None)?;
+		}
+		Ok(())
+	}
+
+	fn holds(who: &AccountId) -> bool {
+		Nfts::owned_in_collection(&Collection::get(), who).next().is_some()
+	}
+}
+
+/// Issues every budget in the same asset, so departments can be funded amounts below the native
+/// token's existential deposit.
+pub struct SharedBudgetAsset<Asset>(sp_std::marker::PhantomData<Asset>);
+impl<Asset: Get<u32>> Convert<u32, Option<u32>> for SharedBudgetAsset<Asset> {
+	fn convert(_budget_id: u32) -> Option<u32> {
+		Some(Asset::get())
+	}
+}
+
+parameter_types! {
+	/// The asset budgets are issued in, created at genesis with a minimum balance of 1 and
+	/// administered by the voting pallet.
+	pub const BudgetAssetId: u32 = u32::MAX;
+	/// The NFT collection citizenship is issued in, well clear of the ids given to collections
+	/// created by users.
+	pub const CitizenshipCollection: u32 = u32::MAX;
+	/// The identity registrar whose judgements let accounts claim citizenship.
+	pub const CitizenshipRegistrar: u32 = 0;
+	pub const VotingPalletId: PalletId = PalletId(*b"py/votng");
+	pub const CarryoverFraction: Perbill = Perbill::from_percent(25);
+}
+
+/// Configure the voting pallet in pallets/voting.
+impl pallet_voting::Config for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type RuntimeEvent = RuntimeEvent;
+	type BudgetId = u32;
+	type VotingCredit = u64;
+	type Funds = u64;
+	type NativeBalance = Balances;
+	type Fungibles = Assets;
+	type PalletId = VotingPalletId;
+	type BudgetAsset = SharedBudgetAsset<BudgetAssetId>;
+	type CreditAsset = ();
+	type RegistrarOrigin = EnsureRoot<AccountId>;
+	/// Elections are opened, extended, cancelled and certified by the electoral commission.
+	type ElectionAdminOrigin = CommissionSupermajority;
+	type TreasuryOrigin = EnsureRoot<AccountId>;
+	type RuntimeCall = RuntimeCall;
+	type GivenVotingCredit = ConstU64<4096>;
+	type CreditAllocator = pallet_voting::FlatCredit;
+	type Contributions = Voting;
+	type MaxRegisteredCitizens = ConstU32<1_000_000>;
+	type MaxBudgetElections = ConstU32<1_000>;
+	type BudgetLifetime = ConstU32<{ 7 * DAYS }>;
+	type UnixTime = Timestamp;
+	type CarryoverFraction = CarryoverFraction;
+	type MaxCarryover = ConstU64<1_024>;
+	type MinNetFunds = ConstU64<0>;
+	type IdentityVerifier = IdentityJudgement<CitizenshipRegistrar>;
+	type Credential = SoulboundCitizenship<CitizenshipCollection>;
+	type RegistrationDeposit = ConstU128<{ 100 * EXISTENTIAL_DEPOSIT }>;
+	type MaxCitizensPerCall = ConstU32<1_000>;
+	type CitizensPerBlock = ConstU32<500>;
+	// Counting a ballot takes around 5ms, so a batch takes an eighth of the 2s block
+	type VotersPerBlock = ConstU32<50>;
+	type WeightInfo = pallet_voting::weights::SubstrateWeight<Runtime>;
+}
+
 // Create the runtime by composing the FRAME pallets that were previously configured.
 construct_runtime!(
 	pub struct Runtime {
@@ -286,6 +533,11 @@ construct_runtime!(
 		Sudo: pallet_sudo,
 		// Include the custom logic from the pallet-template in the runtime.
 		TemplateModule: pallet_template,
+		Assets: pallet_assets,
+		Identity: pallet_identity,
+		Nfts: pallet_nfts,
+		ElectoralCommission: pallet_collective::<Instance1>,
+		Voting: pallet_voting,
 	}
 );
 
@@ -312,6 +564,8 @@ pub type UncheckedExtrinsic =
 	generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
+/// Migrations to run on runtime upgrade.
+pub type Migrations = (pallet_voting::migrations::v1::MigrateToV1<Runtime>,);
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
 	Runtime,
@@ -319,6 +573,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -333,6 +588,11 @@ mod benches {
 		[pallet_balances, Balances]
 		[pallet_timestamp, Timestamp]
 		[pallet_sudo, Sudo]
+		[pallet_assets, Assets]
+		[pallet_collective, ElectoralCommission]
+		[pallet_identity, Identity]
+		[pallet_nfts, Nfts]
+		[pallet_voting, Voting]
 		[pallet_template, TemplateModule]
 	);
 }